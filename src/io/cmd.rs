@@ -1,39 +1,53 @@
 use std::{
-    io,
-    process::{Command, Output, Stdio},
+    io::{self, BufRead},
+    process::Output,
 };
 
-use anyhow::Context;
+use crate::{
+    Result,
+    SysxError,
+    time::{SleepError, SleepTime},
+};
+
+pub mod builder;
+mod child;
+pub mod jobs;
+pub mod output;
+pub mod pipeline;
+pub mod process;
+pub mod stream;
 
-use crate::{Result, SysxError};
+pub use builder::*;
+pub use jobs::*;
+pub use output::*;
+pub use pipeline::*;
+pub use process::*;
+pub use stream::*;
 
 /// Executes a command silently and returns its Output.
 pub fn slrun(command_line: &str) -> Result<Output> {
-    let trimmed = command_line.trim();
-
-    if trimmed.is_empty() {
-        return Err(SysxError::AnyhowError(anyhow::anyhow!(
-            "Empty command line"
-        )));
-    }
-
-    let mut parts = shell_words::split(trimmed)
-        .context("Failed to parse command line")
-        .map_err(SysxError::AnyhowError)?;
+    Cmd::parse(command_line)?.output()
+}
 
-    let program = parts.remove(0);
-    let args = parts;
+/// Executes a command silently and fails with `SysxError::CommandFailed` on a non-zero exit.
+pub fn slrun_checked(command_line: &str) -> Result<CmdOutput> {
+    Cmd::parse(command_line)?.run_checked()
+}
 
-    let output: Output = Command::new(&program)
-        .args(&args)
-        .stderr(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stdin(Stdio::piped())
-        .output()
-        .with_context(|| format!("Failed to execute command '{command_line}'"))
-        .map_err(SysxError::AnyhowError)?;
+/// Executes a command silently, killing it if it runs longer than `timeout`.
+///
+/// Returns `SysxError::CommandTimeout` with the output captured so far on expiry.
+pub fn slrun_timeout<T>(command_line: &str, timeout: T) -> Result<Output>
+where
+    T: TryInto<SleepTime>,
+    T::Error: Into<SleepError>,
+{
+    Cmd::parse(command_line)?.timeout(timeout)?.output()
+}
 
-    Ok(output)
+/// Executes a command line with `|`, `&&`, `||`, `<`, `>` and `>>` operators, without a shell.
+pub fn slrun_pipeline(command_line: &str) -> Result<PipelineOutput> {
+    Pipeline::parse(command_line)?.run()
 }
 
 /// Executes a command, prints stdout, and returns its Output.
@@ -46,6 +60,26 @@ pub fn run(command: &str) -> Result<Output> {
     Ok(output)
 }
 
+/// Executes a command, echoing stdout and stderr live as they arrive, and returns its Output.
+pub fn run_tee(command_line: &str) -> Result<Output> {
+    Cmd::parse(command_line)?.tee()
+}
+
+/// Spawns a command and returns an iterator over its output lines, tagged by stream.
+pub fn stream_lines(command_line: &str) -> Result<LineStream> {
+    Cmd::parse(command_line)?.stream()
+}
+
+/// Starts a command in the background and returns a handle to it.
+pub fn spawn(command_line: &str) -> Result<Process> {
+    Cmd::parse(command_line)?.spawn()
+}
+
+/// Runs command lines concurrently on up to `workers` threads; results keep input order.
+pub fn run_parallel<S: AsRef<str>>(command_lines: &[S], workers: usize) -> Vec<JobResult> {
+    JobRunner::new(workers).run_lines(command_lines)
+}
+
 /// Macro to call `slrun` with a formatted command string.
 #[macro_export]
 macro_rules! slrunf {
@@ -66,18 +100,25 @@ macro_rules! runf {
 }
 pub use runf;
 
-/// Reads a line from stdin into the provided buffer, removing the newline.
-pub fn input_buf(buffer: &mut String) -> Result<()> {
-    io::stdin()
+/// Reads a line from `reader` into the provided buffer, removing the newline.
+///
+/// Returns the number of bytes read; `0` means end of input.
+pub fn input_from<R: BufRead>(reader: &mut R, buffer: &mut String) -> Result<usize> {
+    reader
         .read_line(buffer)
         .map_err(|e| SysxError::AnyhowError(anyhow::anyhow!("Failed to read line: {}", e)))
-        .map(|_| {
+        .inspect(|_| {
             if buffer.ends_with('\n') {
                 buffer.pop();
             }
         })
 }
 
+/// Reads a line from stdin into the provided buffer, removing the newline.
+pub fn input_buf(buffer: &mut String) -> Result<()> {
+    input_from(&mut io::stdin().lock(), buffer).map(|_| ())
+}
+
 /// Reads a line from stdin and returns a new String.
 pub fn input() -> Result<String> {
     let mut input_text = String::new();
@@ -0,0 +1,386 @@
+use std::{
+    ffi::{OsStr, OsString},
+    fmt,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Child, Command, Output, Stdio},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use anyhow::Context;
+
+use super::child::{CapturedPipe, terminate, wait_timeout};
+use crate::{
+    Result,
+    SysxError,
+    time::{SleepError, SleepTime},
+};
+
+/// Time a timed-out child is given to exit after `SIGTERM` before it is killed.
+pub const DEFAULT_KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// How a standard stream of a child process is connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StdioMode {
+    /// Connected to a pipe; output is captured, stdin is fed from `stdin_data`.
+    #[default]
+    Piped,
+    /// Inherited from the current process.
+    Inherit,
+    /// Connected to the null device.
+    Null,
+}
+
+impl From<StdioMode> for Stdio {
+    fn from(mode: StdioMode) -> Self {
+        match mode {
+            StdioMode::Piped => Stdio::piped(),
+            StdioMode::Inherit => Stdio::inherit(),
+            StdioMode::Null => Stdio::null(),
+        }
+    }
+}
+
+/// Builder for a single command invocation.
+///
+/// Arguments are passed to the program as-is; no shell is involved.
+#[derive(Debug, Clone)]
+pub struct Cmd {
+    program:    OsString,
+    args:       Vec<OsString>,
+    cwd:        Option<PathBuf>,
+    env_clear:  bool,
+    envs:       Vec<(OsString, Option<OsString>)>,
+    stdin:      StdioMode,
+    stdin_data: Option<Vec<u8>>,
+    stdout:     StdioMode,
+    stderr:     StdioMode,
+    timeout:    Option<Duration>,
+    kill_grace: Duration,
+}
+
+impl Cmd {
+    /// Creates a builder for `program` with no arguments and all streams piped.
+    pub fn new<S: AsRef<OsStr>>(program: S) -> Self {
+        Self {
+            program:    program.as_ref().to_owned(),
+            args:       Vec::new(),
+            cwd:        None,
+            env_clear:  false,
+            envs:       Vec::new(),
+            stdin:      StdioMode::Piped,
+            stdin_data: None,
+            stdout:     StdioMode::Piped,
+            stderr:     StdioMode::Piped,
+            timeout:    None,
+            kill_grace: DEFAULT_KILL_GRACE,
+        }
+    }
+
+    /// Parses a command line with shell-like quoting rules (no shell is spawned).
+    pub fn parse(command_line: &str) -> Result<Self> {
+        let trimmed = command_line.trim();
+
+        if trimmed.is_empty() {
+            return Err(SysxError::AnyhowError(anyhow::anyhow!(
+                "Empty command line"
+            )));
+        }
+
+        let parts = shell_words::split(trimmed)
+            .context("Failed to parse command line")
+            .map_err(SysxError::AnyhowError)?;
+
+        Self::from_argv(parts)
+    }
+
+    /// Creates a builder from an argument vector whose first element is the program.
+    pub fn from_argv<I, S>(argv: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut argv = argv.into_iter();
+        let program = argv
+            .next()
+            .ok_or_else(|| SysxError::AnyhowError(anyhow::anyhow!("Empty argument vector")))?;
+
+        Ok(Self::new(program).args(argv))
+    }
+
+    /// Appends a single argument.
+    pub fn arg<S: AsRef<OsStr>>(mut self, arg: S) -> Self {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    /// Appends several arguments.
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|a| a.as_ref().to_owned()));
+        self
+    }
+
+    /// Sets the working directory of the child process.
+    pub fn current_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.cwd = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    /// Sets an environment variable for the child process.
+    pub fn env<K: AsRef<OsStr>, V: AsRef<OsStr>>(mut self, key: K, value: V) -> Self {
+        self.envs
+            .push((key.as_ref().to_owned(), Some(value.as_ref().to_owned())));
+        self
+    }
+
+    /// Sets several environment variables for the child process.
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        for (key, value) in vars {
+            self = self.env(key, value);
+        }
+        self
+    }
+
+    /// Removes an environment variable from the child's environment.
+    pub fn env_remove<K: AsRef<OsStr>>(mut self, key: K) -> Self {
+        self.envs.push((key.as_ref().to_owned(), None));
+        self
+    }
+
+    /// Starts the child with an empty environment (before applying `env` calls).
+    pub fn env_clear(mut self) -> Self {
+        self.env_clear = true;
+        self.envs.clear();
+        self
+    }
+
+    /// Feeds `data` to the child's stdin, then closes it.
+    pub fn stdin_data<D: Into<Vec<u8>>>(mut self, data: D) -> Self {
+        self.stdin = StdioMode::Piped;
+        self.stdin_data = Some(data.into());
+        self
+    }
+
+    /// Configures the child's stdin. `Piped` without data yields an empty stdin.
+    pub fn stdin(mut self, mode: StdioMode) -> Self {
+        self.stdin = mode;
+        if mode != StdioMode::Piped {
+            self.stdin_data = None;
+        }
+        self
+    }
+
+    /// Configures the child's stdout.
+    pub fn stdout(mut self, mode: StdioMode) -> Self {
+        self.stdout = mode;
+        self
+    }
+
+    /// Configures the child's stderr.
+    pub fn stderr(mut self, mode: StdioMode) -> Self {
+        self.stderr = mode;
+        self
+    }
+
+    /// Inherits stdout and stderr from the current process instead of capturing them.
+    pub fn inherit_output(self) -> Self {
+        self.stdout(StdioMode::Inherit).stderr(StdioMode::Inherit)
+    }
+
+    /// Kills the command if it runs longer than `timeout`.
+    ///
+    /// On Unix the command runs in its own process group unless stdin is inherited, so
+    /// processes it started are stopped as well.
+    ///
+    /// Accepts the same inputs as `time::sleep`: `u64` (ms), `f64` (s), `&str` ("30s")
+    /// or `Duration`.
+    pub fn timeout<T>(mut self, timeout: T) -> Result<Self>
+    where
+        T: TryInto<SleepTime>,
+        T::Error: Into<SleepError>,
+    {
+        let timeout = timeout.try_into().map_err(Into::into)?;
+        self.timeout = Some(timeout.to_duration());
+        Ok(self)
+    }
+
+    /// Sets how long a timed-out child may take to exit after `SIGTERM` before `SIGKILL`.
+    pub fn kill_grace<T>(mut self, grace: T) -> Result<Self>
+    where
+        T: TryInto<SleepTime>,
+        T::Error: Into<SleepError>,
+    {
+        let grace = grace.try_into().map_err(Into::into)?;
+        self.kill_grace = grace.to_duration();
+        Ok(self)
+    }
+
+    /// Returns the configured timeout, if any.
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Returns the grace period between `SIGTERM` and `SIGKILL`.
+    pub fn get_kill_grace(&self) -> Duration {
+        self.kill_grace
+    }
+
+    /// Returns the program name.
+    pub fn get_program(&self) -> &OsStr {
+        &self.program
+    }
+
+    /// Returns the arguments (without the program name).
+    pub fn get_args(&self) -> &[OsString] {
+        &self.args
+    }
+
+    /// Returns the command line as a shell-quoted string, for messages and logs.
+    pub fn command_line(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|part| shell_words::quote(&part.to_string_lossy()).into_owned())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Builds the equivalent `std::process::Command`.
+    pub fn to_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+
+        if let Some(dir) = &self.cwd {
+            command.current_dir(dir);
+        }
+        if self.env_clear {
+            command.env_clear();
+        }
+        for (key, value) in &self.envs {
+            match value {
+                Some(value) => command.env(key, value),
+                None => command.env_remove(key),
+            };
+        }
+
+        command
+            .stdin(self.stdin)
+            .stdout(self.stdout)
+            .stderr(self.stderr);
+        command
+    }
+
+    /// Spawns the child and starts feeding stdin data, if any.
+    pub(crate) fn spawn_child(&self) -> Result<(Child, Option<JoinHandle<()>>)> {
+        self.spawn_child_with(|_| {})
+    }
+
+    /// Like `spawn_child`, but lets the caller adjust the `Command` (e.g. rewire stdio) first.
+    pub(crate) fn spawn_child_with<F>(
+        &self,
+        configure: F,
+    ) -> Result<(Child, Option<JoinHandle<()>>)>
+    where
+        F: FnOnce(&mut Command),
+    {
+        let mut command = self.to_command();
+        // A process group of its own lets timeouts and kills reach the child's descendants.
+        // Commands reading an inherited stdin stay in the terminal's foreground group.
+        #[cfg(unix)]
+        if self.stdin != StdioMode::Inherit {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        configure(&mut command);
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to execute command '{}'", self.command_line()))
+            .map_err(SysxError::AnyhowError)?;
+
+        let stdin = child.stdin.take();
+        let writer = match (stdin, &self.stdin_data) {
+            (Some(mut stdin), Some(data)) => {
+                let data = data.clone();
+                // The child may exit without reading everything; a broken pipe is not an error.
+                Some(thread::spawn(move || {
+                    let _ = stdin.write_all(&data);
+                }))
+            }
+            _ => None,
+        };
+
+        Ok((child, writer))
+    }
+
+    /// Runs the command to completion and collects its output.
+    ///
+    /// With a timeout set, returns `SysxError::CommandTimeout` if the deadline passes.
+    pub fn output(&self) -> Result<Output> {
+        if let Some(timeout) = self.timeout {
+            return self.output_with_timeout(timeout);
+        }
+
+        let (child, writer) = self.spawn_child()?;
+
+        let output = child
+            .wait_with_output()
+            .with_context(|| format!("Failed to wait for command '{}'", self.command_line()))
+            .map_err(SysxError::AnyhowError)?;
+
+        if let Some(writer) = writer {
+            let _ = writer.join();
+        }
+
+        Ok(output)
+    }
+
+    fn output_with_timeout(&self, timeout: Duration) -> Result<Output> {
+        let (mut child, writer) = self.spawn_child()?;
+        let stdout = child.stdout.take().map(CapturedPipe::spawn);
+        let stderr = child.stderr.take().map(CapturedPipe::spawn);
+
+        let status = wait_timeout(&mut child, timeout)
+            .with_context(|| format!("Failed to wait for command '{}'", self.command_line()))
+            .map_err(SysxError::AnyhowError)?;
+
+        let Some(status) = status else {
+            terminate(&mut child, self.kill_grace)
+                .with_context(|| format!("Failed to kill command '{}'", self.command_line()))
+                .map_err(SysxError::AnyhowError)?;
+
+            return Err(SysxError::CommandTimeout {
+                command: self.command_line(),
+                timeout,
+                stdout: stdout.map(CapturedPipe::settle).unwrap_or_default(),
+                stderr: stderr.map(CapturedPipe::settle).unwrap_or_default(),
+            });
+        };
+
+        if let Some(writer) = writer {
+            let _ = writer.join();
+        }
+
+        Ok(Output {
+            status,
+            stdout: stdout.map(CapturedPipe::join).unwrap_or_default(),
+            stderr: stderr.map(CapturedPipe::join).unwrap_or_default(),
+        })
+    }
+}
+
+impl fmt::Display for Cmd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.command_line())
+    }
+}
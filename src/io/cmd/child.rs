@@ -0,0 +1,156 @@
+use std::{
+    io::{self, Read},
+    process::{Child, ExitStatus},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Upper bound for a single `try_wait` polling interval.
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time given to reader threads to drain a pipe after the child was killed.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Background reader that accumulates everything written to a child's pipe.
+pub(crate) struct CapturedPipe {
+    buf:    Arc<Mutex<Vec<u8>>>,
+    handle: JoinHandle<()>,
+}
+
+impl CapturedPipe {
+    /// Starts reading `reader` on a new thread until EOF.
+    pub(crate) fn spawn<R: Read + Send + 'static>(mut reader: R) -> Self {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&buf);
+
+        let handle = thread::spawn(move || {
+            let mut chunk = [0u8; 8192];
+            loop {
+                match reader.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(n) => lock(&sink).extend_from_slice(&chunk[..n]),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Self { buf, handle }
+    }
+
+    /// Returns a copy of the bytes read so far, starting at `offset`.
+    pub(crate) fn read_from(&self, offset: usize) -> Vec<u8> {
+        let buf = lock(&self.buf);
+        buf.get(offset..).map(<[u8]>::to_vec).unwrap_or_default()
+    }
+
+    /// Waits for EOF and returns everything that was read.
+    pub(crate) fn join(self) -> Vec<u8> {
+        let _ = self.handle.join();
+        std::mem::take(&mut *lock(&self.buf))
+    }
+
+    /// Gives the reader a short time to drain the pipe, then returns what was read so far.
+    ///
+    /// Used after a kill, when grandchildren may keep the pipe open indefinitely.
+    pub(crate) fn settle(self) -> Vec<u8> {
+        let deadline = Instant::now() + SETTLE_TIME;
+        while !self.handle.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        std::mem::take(&mut *lock(&self.buf))
+    }
+}
+
+/// Locks a capture buffer, ignoring poisoning (the data is plain bytes).
+fn lock(buf: &Mutex<Vec<u8>>) -> std::sync::MutexGuard<'_, Vec<u8>> {
+    buf.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Waits for the child to exit, giving up after `timeout`.
+pub(crate) fn wait_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    let mut interval = Duration::from_millis(1);
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+
+        thread::sleep(interval.min(deadline - now));
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    }
+}
+
+/// Asks the child to terminate, then kills it if it is still alive after `grace`.
+///
+/// On Unix this sends `SIGTERM` first; elsewhere the child is killed immediately. Signals
+/// reach the child's whole process group (see `send_signal`).
+pub(crate) fn terminate(child: &mut Child, grace: Duration) -> io::Result<ExitStatus> {
+    if let Some(status) = child.try_wait()? {
+        return Ok(status);
+    }
+
+    #[cfg(unix)]
+    {
+        send_signal(child, libc::SIGTERM)?;
+        if let Some(status) = wait_timeout(child, grace)? {
+            return Ok(status);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = grace;
+
+    kill(child)?;
+    child.wait()
+}
+
+/// Kills the child (`SIGKILL` to its process group on Unix) without reaping it.
+pub(crate) fn kill(child: &mut Child) -> io::Result<()> {
+    #[cfg(unix)]
+    let result = send_signal(child, libc::SIGKILL);
+    #[cfg(not(unix))]
+    let result = child.kill();
+
+    match result {
+        Ok(()) => Ok(()),
+        // Already exited and reaped.
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Sends a raw signal to the child process.
+///
+/// If the child leads its own process group (see `Cmd::spawn_child_with`), the whole group
+/// is signalled, so descendants such as the commands run by `sh -c` are reached too.
+#[cfg(unix)]
+pub(crate) fn send_signal(child: &Child, signal: i32) -> io::Result<()> {
+    let pid = libc::pid_t::try_from(child.id())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Invalid process id"))?;
+
+    // SAFETY: `kill` has no memory-safety preconditions; the pid belongs to a child we
+    // have not reaped yet, so neither it nor a process group with the same id can refer to
+    // unrelated processes.
+    if unsafe { libc::kill(-pid, signal) } == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    if err.raw_os_error() != Some(libc::ESRCH) {
+        return Err(err);
+    }
+
+    // No such group: the child shares ours, so signal it alone.
+    // SAFETY: as above.
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
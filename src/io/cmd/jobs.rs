@@ -0,0 +1,171 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{Cmd, CmdOutput};
+use crate::{Result, SysxError};
+
+/// A job as submitted to the runner.
+enum Job {
+    Ready(Cmd),
+    /// Command line that failed to parse, with the parse error message.
+    Invalid {
+        line:  String,
+        error: String,
+    },
+}
+
+impl Job {
+    fn command_line(&self) -> String {
+        match self {
+            Job::Ready(cmd) => cmd.command_line(),
+            Job::Invalid { line, .. } => line.clone(),
+        }
+    }
+
+    fn skipped(&self) -> JobResult {
+        JobResult {
+            command:  self.command_line(),
+            result:   None,
+            duration: Duration::ZERO,
+        }
+    }
+
+    fn run(&self) -> JobResult {
+        let started = Instant::now();
+        let result = match self {
+            Job::Ready(cmd) => cmd.run_checked(),
+            Job::Invalid { error, .. } => Err(SysxError::InvalidSyntax(error.clone())),
+        };
+
+        JobResult {
+            command:  self.command_line(),
+            result:   Some(result),
+            duration: started.elapsed(),
+        }
+    }
+}
+
+/// Outcome of a single job run by a `JobRunner`.
+#[derive(Debug)]
+pub struct JobResult {
+    /// Command line of the job.
+    pub command:  String,
+    /// Checked command output, or `None` if the job was skipped after an earlier failure.
+    pub result:   Option<Result<CmdOutput>>,
+    /// Wall-clock time the job took.
+    pub duration: Duration,
+}
+
+impl JobResult {
+    /// Returns `true` if the job ran and exited successfully.
+    pub fn success(&self) -> bool {
+        matches!(self.result, Some(Ok(_)))
+    }
+
+    /// Returns `true` if the job was never started because of `fail_fast`.
+    pub fn skipped(&self) -> bool {
+        self.result.is_none()
+    }
+}
+
+/// Runs many commands concurrently with a bounded number of workers.
+///
+/// Results are returned in input order. A job fails if it cannot be parsed or started,
+/// or exits unsuccessfully (`SysxError::CommandFailed`).
+#[derive(Debug, Clone)]
+pub struct JobRunner {
+    workers:   usize,
+    fail_fast: bool,
+}
+
+impl Default for JobRunner {
+    /// One worker per available CPU.
+    fn default() -> Self {
+        Self::new(
+            thread::available_parallelism()
+                .map(usize::from)
+                .unwrap_or(1),
+        )
+    }
+}
+
+impl JobRunner {
+    /// Creates a runner with at most `workers` jobs running at a time.
+    pub fn new(workers: usize) -> Self {
+        Self {
+            workers:   workers.max(1),
+            fail_fast: false,
+        }
+    }
+
+    /// Stops starting new jobs after the first failure; jobs already running finish.
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Runs prepared commands.
+    pub fn run<I: IntoIterator<Item = Cmd>>(&self, jobs: I) -> Vec<JobResult> {
+        self.execute(jobs.into_iter().map(Job::Ready).collect())
+    }
+
+    /// Runs command lines, parsed the same way as `slrun`.
+    pub fn run_lines<S: AsRef<str>>(&self, lines: &[S]) -> Vec<JobResult> {
+        self.execute(
+            lines
+                .iter()
+                .map(|line| match Cmd::parse(line.as_ref()) {
+                    Ok(cmd) => Job::Ready(cmd),
+                    Err(e) => Job::Invalid {
+                        line:  line.as_ref().to_owned(),
+                        error: format!("{e}: '{}'", line.as_ref()),
+                    },
+                })
+                .collect(),
+        )
+    }
+
+    fn execute(&self, jobs: Vec<Job>) -> Vec<JobResult> {
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let slots: Mutex<Vec<Option<JobResult>>> = Mutex::new(jobs.iter().map(|_| None).collect());
+
+        thread::scope(|scope| {
+            for _ in 0..self.workers.min(jobs.len()) {
+                scope.spawn(|| {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(job) = jobs.get(index) else {
+                            break;
+                        };
+
+                        let result = if self.fail_fast && failed.load(Ordering::SeqCst) {
+                            job.skipped()
+                        } else {
+                            job.run()
+                        };
+
+                        if !result.success() && !result.skipped() {
+                            failed.store(true, Ordering::SeqCst);
+                        }
+                        slots.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+                    }
+                });
+            }
+        });
+
+        slots
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .into_iter()
+            .zip(&jobs)
+            .map(|(slot, job)| slot.unwrap_or_else(|| job.skipped()))
+            .collect()
+    }
+}
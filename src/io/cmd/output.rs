@@ -0,0 +1,129 @@
+use std::{
+    borrow::Cow,
+    process::{ExitStatus, Output},
+};
+
+use super::Cmd;
+use crate::{Result, SysxError};
+
+/// Number of trailing stderr lines kept in `SysxError::CommandFailed`.
+pub const STDERR_TAIL_LINES: usize = 10;
+
+/// Output of a finished command together with the command line that produced it.
+#[derive(Debug, Clone)]
+pub struct CmdOutput {
+    command: String,
+    output:  Output,
+}
+
+impl CmdOutput {
+    /// Wraps a raw `Output` produced by `command`.
+    pub fn new<S: Into<String>>(command: S, output: Output) -> Self {
+        Self {
+            command: command.into(),
+            output,
+        }
+    }
+
+    /// Returns the command line that produced this output.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Returns the exit status.
+    pub fn status(&self) -> ExitStatus {
+        self.output.status
+    }
+
+    /// Returns `true` if the command exited with code 0.
+    pub fn success(&self) -> bool {
+        self.output.status.success()
+    }
+
+    /// Returns the exit code, or `None` if the command was terminated by a signal.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.output.status.code()
+    }
+
+    /// Returns the signal that terminated the command (always `None` outside Unix).
+    pub fn signal(&self) -> Option<i32> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            self.output.status.signal()
+        }
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+
+    /// Returns the raw stdout bytes.
+    pub fn stdout(&self) -> &[u8] {
+        &self.output.stdout
+    }
+
+    /// Returns the raw stderr bytes.
+    pub fn stderr(&self) -> &[u8] {
+        &self.output.stderr
+    }
+
+    /// Returns stdout decoded as UTF-8, replacing invalid sequences.
+    pub fn stdout_str(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.output.stdout)
+    }
+
+    /// Returns stderr decoded as UTF-8, replacing invalid sequences.
+    pub fn stderr_str(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.output.stderr)
+    }
+
+    /// Returns the lines of stdout, without line terminators.
+    pub fn lines(&self) -> Vec<String> {
+        self.stdout_str().lines().map(str::to_owned).collect()
+    }
+
+    /// Returns the last `STDERR_TAIL_LINES` lines of stderr.
+    pub fn stderr_tail(&self) -> String {
+        let stderr = self.stderr_str();
+        let lines: Vec<&str> = stderr.trim_end().lines().collect();
+        lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n")
+    }
+
+    /// Converts an unsuccessful exit into `SysxError::CommandFailed`.
+    pub fn check(self) -> Result<Self> {
+        if self.success() {
+            return Ok(self);
+        }
+
+        Err(SysxError::CommandFailed {
+            code:    self.exit_code(),
+            signal:  self.signal(),
+            stderr:  self.stderr_tail(),
+            command: self.command,
+        })
+    }
+
+    /// Returns the underlying `Output`.
+    pub fn into_output(self) -> Output {
+        self.output
+    }
+}
+
+impl From<CmdOutput> for Output {
+    fn from(output: CmdOutput) -> Self {
+        output.output
+    }
+}
+
+impl Cmd {
+    /// Runs the command and returns a `CmdOutput`, regardless of the exit status.
+    pub fn capture(&self) -> Result<CmdOutput> {
+        Ok(CmdOutput::new(self.command_line(), self.output()?))
+    }
+
+    /// Runs the command and fails with `SysxError::CommandFailed` on a non-zero exit.
+    pub fn run_checked(&self) -> Result<CmdOutput> {
+        self.capture()?.check()
+    }
+}
@@ -0,0 +1,530 @@
+use std::{
+    fmt,
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+    process::{Child, ExitStatus, Output, Stdio},
+};
+
+use anyhow::Context;
+
+use super::{
+    Cmd,
+    child::{CapturedPipe, kill},
+};
+use crate::{Result, SysxError};
+
+/// Operator joining two pipelines in a command list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connector {
+    /// `&&`: run the next pipeline only if the previous one succeeded.
+    And,
+    /// `||`: run the next pipeline only if the previous one failed.
+    Or,
+}
+
+/// A single command of a pipeline with its file redirections.
+#[derive(Debug, Clone)]
+struct Stage {
+    cmd:    Cmd,
+    input:  Option<PathBuf>,
+    output: Option<(PathBuf, bool)>,
+}
+
+impl Stage {
+    fn new(cmd: Cmd) -> Self {
+        Self {
+            cmd,
+            input: None,
+            output: None,
+        }
+    }
+}
+
+/// Commands connected with `|`, optionally preceded by `&&` / `||`.
+#[derive(Debug, Clone)]
+struct PipeGroup {
+    connector: Option<Connector>,
+    stages:    Vec<Stage>,
+}
+
+/// Exit status of one command of a pipeline.
+#[derive(Debug, Clone)]
+pub struct StageStatus {
+    /// Command line of the stage.
+    pub command: String,
+    /// Exit status of the stage.
+    pub status:  ExitStatus,
+}
+
+/// Result of running a `Pipeline`.
+#[derive(Debug, Clone)]
+pub struct PipelineOutput {
+    /// Statuses of every stage that was run, in execution order.
+    pub stages: Vec<StageStatus>,
+    /// Status of the last stage run, plus all captured stdout and stderr.
+    pub output: Output,
+}
+
+impl PipelineOutput {
+    /// Returns `true` if the last stage that ran exited successfully.
+    pub fn success(&self) -> bool {
+        self.output.status.success()
+    }
+}
+
+/// Shell-like command list executed without a shell.
+///
+/// Supports `|`, `&&`, `||`, `<`, `>` and `>>`. Processes are connected with OS pipes;
+/// stdout of every executed pipeline and stderr of every stage are captured.
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    groups: Vec<PipeGroup>,
+}
+
+/// Lexical token of a pipeline command line.
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Pipe,
+    And,
+    Or,
+    Read,
+    Write,
+    Append,
+}
+
+impl Token {
+    fn as_str(&self) -> &str {
+        match self {
+            Token::Word(word) => word,
+            Token::Pipe => "|",
+            Token::And => "&&",
+            Token::Or => "||",
+            Token::Read => "<",
+            Token::Write => ">",
+            Token::Append => ">>",
+        }
+    }
+}
+
+/// Splits a command line at unquoted operators and tokenizes the rest with `shell_words`.
+fn tokenize(line: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut segment = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some('\'') => {
+                segment.push(c);
+                if c == '\'' {
+                    quote = None;
+                }
+            }
+            Some(_) => {
+                segment.push(c);
+                if c == '\\' {
+                    segment.extend(chars.next());
+                } else if c == '"' {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '\'' | '"' => {
+                    quote = Some(c);
+                    segment.push(c);
+                }
+                '\\' => {
+                    segment.push(c);
+                    segment.extend(chars.next());
+                }
+                '|' | '&' | '<' | '>' => {
+                    push_words(&mut segment, &mut tokens)?;
+                    let token = match (c, chars.peek()) {
+                        ('|', Some('|')) => Token::Or,
+                        ('|', _) => Token::Pipe,
+                        ('&', Some('&')) => Token::And,
+                        ('&', _) => {
+                            return Err(SysxError::InvalidSyntax(
+                                "Background operator '&' is not supported".into(),
+                            ));
+                        }
+                        ('>', Some('>')) => Token::Append,
+                        ('>', _) => Token::Write,
+                        _ => Token::Read,
+                    };
+                    if matches!(token, Token::Or | Token::And | Token::Append) {
+                        chars.next();
+                    }
+                    tokens.push(token);
+                }
+                _ => segment.push(c),
+            },
+        }
+    }
+    push_words(&mut segment, &mut tokens)?;
+
+    Ok(tokens)
+}
+
+/// Tokenizes an operator-free segment and appends its words.
+fn push_words(segment: &mut String, tokens: &mut Vec<Token>) -> Result<()> {
+    if !segment.trim().is_empty() {
+        let words = shell_words::split(segment)
+            .context("Failed to parse command line")
+            .map_err(SysxError::AnyhowError)?;
+        tokens.extend(words.into_iter().map(Token::Word));
+    }
+    segment.clear();
+    Ok(())
+}
+
+impl Pipeline {
+    /// Creates a pipeline consisting of a single command.
+    pub fn new(cmd: Cmd) -> Self {
+        Self {
+            groups: vec![PipeGroup {
+                connector: None,
+                stages:    vec![Stage::new(cmd)],
+            }],
+        }
+    }
+
+    /// Parses a command line such as `"grep foo < in.txt | sort && echo done > out.txt"`.
+    pub fn parse(command_line: &str) -> Result<Self> {
+        let mut tokens = tokenize(command_line)?.into_iter();
+        let mut groups: Vec<PipeGroup> = Vec::new();
+        let mut connector = None;
+        let mut stages = Vec::new();
+        let mut words = Vec::new();
+        let mut input = None;
+        let mut output = None;
+
+        loop {
+            let token = tokens.next();
+            match token {
+                Some(Token::Word(word)) => words.push(word),
+                Some(op @ (Token::Read | Token::Write | Token::Append)) => {
+                    let Some(Token::Word(path)) = tokens.next() else {
+                        return Err(SysxError::InvalidSyntax(format!(
+                            "Missing file name after '{}'",
+                            op.as_str()
+                        )));
+                    };
+                    match op {
+                        Token::Read => input = Some(PathBuf::from(path)),
+                        _ => output = Some((PathBuf::from(path), op == Token::Append)),
+                    }
+                }
+                Some(Token::Pipe | Token::And | Token::Or) | None => {
+                    if words.is_empty() {
+                        if token.is_none() && groups.is_empty() && stages.is_empty() {
+                            return Err(SysxError::AnyhowError(anyhow::anyhow!(
+                                "Empty command line"
+                            )));
+                        }
+                        return Err(SysxError::InvalidSyntax(format!(
+                            "Missing command near '{}'",
+                            token.as_ref().map_or("end of line", Token::as_str)
+                        )));
+                    }
+
+                    stages.push(Stage {
+                        cmd:    Cmd::from_argv(words.drain(..))?,
+                        input:  input.take(),
+                        output: output.take(),
+                    });
+
+                    match token {
+                        Some(Token::Pipe) => {}
+                        Some(next) => {
+                            groups.push(PipeGroup {
+                                connector,
+                                stages: std::mem::take(&mut stages),
+                            });
+                            connector = Some(if next == Token::And {
+                                Connector::And
+                            } else {
+                                Connector::Or
+                            });
+                        }
+                        None => {
+                            groups.push(PipeGroup { connector, stages });
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let pipeline = Self { groups };
+        pipeline.validate()?;
+        Ok(pipeline)
+    }
+
+    fn last_group(&mut self) -> &mut PipeGroup {
+        self.groups
+            .last_mut()
+            .expect("Pipeline always has at least one group")
+    }
+
+    fn push_group(mut self, connector: Connector, cmd: Cmd) -> Self {
+        self.groups.push(PipeGroup {
+            connector: Some(connector),
+            stages:    vec![Stage::new(cmd)],
+        });
+        self
+    }
+
+    /// Pipes the output of the current pipeline into `cmd` (`|`).
+    pub fn pipe(mut self, cmd: Cmd) -> Self {
+        self.last_group().stages.push(Stage::new(cmd));
+        self
+    }
+
+    /// Runs `cmd` only if everything so far succeeded (`&&`).
+    pub fn and(self, cmd: Cmd) -> Self {
+        self.push_group(Connector::And, cmd)
+    }
+
+    /// Runs `cmd` only if everything so far failed (`||`).
+    pub fn or(self, cmd: Cmd) -> Self {
+        self.push_group(Connector::Or, cmd)
+    }
+
+    /// Reads stdin of the current pipeline's first command from `path` (`<`).
+    pub fn stdin_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        if let Some(stage) = self.last_group().stages.first_mut() {
+            stage.input = Some(path.as_ref().to_owned());
+        }
+        self
+    }
+
+    /// Writes stdout of the current pipeline's last command to `path` (`>`).
+    pub fn stdout_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        if let Some(stage) = self.last_group().stages.last_mut() {
+            stage.output = Some((path.as_ref().to_owned(), false));
+        }
+        self
+    }
+
+    /// Appends stdout of the current pipeline's last command to `path` (`>>`).
+    pub fn append_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        if let Some(stage) = self.last_group().stages.last_mut() {
+            stage.output = Some((path.as_ref().to_owned(), true));
+        }
+        self
+    }
+
+    /// Checks that redirections only appear where they can take effect.
+    fn validate(&self) -> Result<()> {
+        for group in &self.groups {
+            let last = group.stages.len() - 1;
+            for (i, stage) in group.stages.iter().enumerate() {
+                if stage.input.is_some() && i != 0 {
+                    return Err(SysxError::InvalidSyntax(format!(
+                        "Input redirect is only allowed on the first command of a pipeline: {}",
+                        stage.cmd
+                    )));
+                }
+                if stage.output.is_some() && i != last {
+                    return Err(SysxError::InvalidSyntax(format!(
+                        "Output redirect is only allowed on the last command of a pipeline: {}",
+                        stage.cmd
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the command list and collects per-stage statuses and the combined output.
+    pub fn run(&self) -> Result<PipelineOutput> {
+        self.validate()?;
+
+        let mut stages = Vec::new();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut last_status: Option<ExitStatus> = None;
+
+        for group in &self.groups {
+            let skip = match (group.connector, last_status) {
+                (Some(Connector::And), Some(status)) => !status.success(),
+                (Some(Connector::Or), Some(status)) => status.success(),
+                _ => false,
+            };
+            if skip {
+                continue;
+            }
+
+            let statuses = run_group(group, &mut stdout, &mut stderr)?;
+            last_status = statuses.last().map(|s| s.status);
+            stages.extend(statuses);
+        }
+
+        let status = last_status.expect("First pipeline always runs");
+        Ok(PipelineOutput {
+            stages,
+            output: Output {
+                status,
+                stdout,
+                stderr,
+            },
+        })
+    }
+}
+
+/// Spawns every stage of a `|`-connected group and waits for all of them.
+fn run_group(
+    group: &PipeGroup,
+    stdout: &mut Vec<u8>,
+    stderr: &mut Vec<u8>,
+) -> Result<Vec<StageStatus>> {
+    let last = group.stages.len() - 1;
+    let mut children: Vec<Child> = Vec::with_capacity(group.stages.len());
+    let mut writers = Vec::new();
+    let mut stderr_pipes = Vec::new();
+    let mut stdout_pipe = None;
+    let mut upstream: Option<Stdio> = None;
+
+    for (i, stage) in group.stages.iter().enumerate() {
+        // Redirect files are opened per stage, so a failure here must also clean up
+        // the stages already running.
+        let spawned = open_redirects(stage, upstream.take()).and_then(|(stdin, file_out)| {
+            stage.cmd.spawn_child_with(|command| {
+                if let Some(stdin) = stdin {
+                    command.stdin(stdin);
+                }
+                if let Some(file) = file_out {
+                    command.stdout(file);
+                } else if i != last {
+                    command.stdout(Stdio::piped());
+                }
+            })
+        });
+
+        let (mut child, writer) = match spawned {
+            Ok(spawned) => spawned,
+            Err(err) => {
+                kill_and_reap(&mut children);
+                return Err(err);
+            }
+        };
+
+        if i == last {
+            stdout_pipe = child.stdout.take().map(CapturedPipe::spawn);
+        } else {
+            upstream = child.stdout.take().map(Stdio::from);
+        }
+        stderr_pipes.extend(child.stderr.take().map(CapturedPipe::spawn));
+        writers.extend(writer);
+        children.push(child);
+    }
+
+    let mut statuses = Vec::with_capacity(children.len());
+    let mut failure = None;
+    for (i, stage) in group.stages.iter().enumerate() {
+        let waited = children[i]
+            .wait()
+            .with_context(|| format!("Failed to wait for command '{}'", stage.cmd))
+            .map_err(SysxError::AnyhowError);
+        match waited {
+            Ok(status) => statuses.push(StageStatus {
+                command: stage.cmd.command_line(),
+                status,
+            }),
+            Err(err) => {
+                // Reap the remaining stages so the capture threads below reach EOF.
+                kill_and_reap(&mut children[i..]);
+                failure = Some(err);
+                break;
+            }
+        }
+    }
+
+    for writer in writers {
+        let _ = writer.join();
+    }
+    if let Some(pipe) = stdout_pipe {
+        stdout.extend(pipe.join());
+    }
+    for pipe in stderr_pipes {
+        stderr.extend(pipe.join());
+    }
+
+    match failure {
+        Some(err) => Err(err),
+        None => Ok(statuses),
+    }
+}
+
+/// Kills and reaps stages that are still running after a failure.
+fn kill_and_reap(children: &mut [Child]) {
+    for child in children {
+        if matches!(child.try_wait(), Ok(None)) {
+            let _ = kill(child);
+        }
+        let _ = child.wait();
+    }
+}
+
+fn open_redirects(
+    stage: &Stage,
+    upstream: Option<Stdio>,
+) -> Result<(Option<Stdio>, Option<Stdio>)> {
+    let stdin = match (&stage.input, upstream) {
+        (Some(path), _) => Some(open_input(path)?),
+        (None, pipe) => pipe,
+    };
+    let file_out = match &stage.output {
+        Some((path, append)) => Some(open_output(path, *append)?),
+        None => None,
+    };
+    Ok((stdin, file_out))
+}
+
+fn open_input(path: &Path) -> Result<Stdio> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open '{}' for reading", path.display()))
+        .map_err(SysxError::AnyhowError)?;
+    Ok(file.into())
+}
+
+fn open_output(path: &Path, append: bool) -> Result<Stdio> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .with_context(|| format!("Failed to open '{}' for writing", path.display()))
+        .map_err(SysxError::AnyhowError)?;
+    Ok(file.into())
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for group in &self.groups {
+            match group.connector {
+                Some(Connector::And) => f.write_str(" && ")?,
+                Some(Connector::Or) => f.write_str(" || ")?,
+                None => {}
+            }
+            for (i, stage) in group.stages.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(" | ")?;
+                }
+                write!(f, "{}", stage.cmd)?;
+                if let Some(path) = &stage.input {
+                    write!(f, " < {}", shell_words::quote(&path.to_string_lossy()))?;
+                }
+                if let Some((path, append)) = &stage.output {
+                    let op = if *append { ">>" } else { ">" };
+                    write!(f, " {op} {}", shell_words::quote(&path.to_string_lossy()))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
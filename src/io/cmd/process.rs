@@ -0,0 +1,218 @@
+use std::{
+    process::{Child, ExitStatus, Output},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use anyhow::Context;
+
+use super::{
+    Cmd,
+    CmdOutput,
+    child::{CapturedPipe, kill, terminate, wait_timeout},
+};
+use crate::{
+    Result,
+    SysxError,
+    time::{SleepError, SleepTime},
+};
+
+/// What happens to a still-running child when its `Process` handle is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DropBehavior {
+    /// Kill the child and reap it.
+    #[default]
+    Kill,
+    /// Leave the child running in the background.
+    Detach,
+}
+
+/// Handle to a command running in the background.
+///
+/// Piped stdout/stderr are collected on background threads and can be read at any time.
+pub struct Process {
+    child:      Child,
+    command:    String,
+    stdout:     Option<CapturedPipe>,
+    stderr:     Option<CapturedPipe>,
+    stdout_pos: usize,
+    stderr_pos: usize,
+    writer:     Option<JoinHandle<()>>,
+    status:     Option<ExitStatus>,
+    kill_grace: Duration,
+    on_drop:    DropBehavior,
+}
+
+impl Process {
+    /// Returns the OS process id.
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Returns the command line of the process.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Sets what happens to the child if the handle is dropped while it is still running.
+    pub fn on_drop(mut self, behavior: DropBehavior) -> Self {
+        self.on_drop = behavior;
+        self
+    }
+
+    /// Returns the exit status if the child has exited, without blocking.
+    pub fn try_wait(&mut self) -> Result<Option<ExitStatus>> {
+        if self.status.is_none() {
+            self.status = self
+                .child
+                .try_wait()
+                .with_context(|| format!("Failed to poll command '{}'", self.command))
+                .map_err(SysxError::AnyhowError)?;
+        }
+        Ok(self.status)
+    }
+
+    /// Blocks until the child exits.
+    pub fn wait(&mut self) -> Result<ExitStatus> {
+        if let Some(status) = self.status {
+            return Ok(status);
+        }
+
+        let status = self
+            .child
+            .wait()
+            .with_context(|| format!("Failed to wait for command '{}'", self.command))
+            .map_err(SysxError::AnyhowError)?;
+        self.status = Some(status);
+        Ok(status)
+    }
+
+    /// Waits up to `timeout` for the child to exit; returns `None` if it is still running.
+    ///
+    /// Accepts the same inputs as `time::sleep`.
+    pub fn wait_with_timeout<T>(&mut self, timeout: T) -> Result<Option<ExitStatus>>
+    where
+        T: TryInto<SleepTime>,
+        T::Error: Into<SleepError>,
+    {
+        if let Some(status) = self.status {
+            return Ok(Some(status));
+        }
+
+        let timeout = timeout.try_into().map_err(Into::into)?;
+        self.status = wait_timeout(&mut self.child, timeout.to_duration())
+            .with_context(|| format!("Failed to wait for command '{}'", self.command))
+            .map_err(SysxError::AnyhowError)?;
+        Ok(self.status)
+    }
+
+    /// Waits for the child and returns its complete captured output.
+    pub fn wait_with_output(mut self) -> Result<CmdOutput> {
+        let status = self.wait()?;
+
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        let stdout = self.stdout.take().map(CapturedPipe::join);
+        let stderr = self.stderr.take().map(CapturedPipe::join);
+
+        Ok(CmdOutput::new(
+            self.command.clone(),
+            Output {
+                status,
+                stdout: stdout.unwrap_or_default(),
+                stderr: stderr.unwrap_or_default(),
+            },
+        ))
+    }
+
+    /// Kills the child and its process group immediately (`SIGKILL` on Unix) and reaps it.
+    pub fn kill(&mut self) -> Result<()> {
+        if self.try_wait()?.is_some() {
+            return Ok(());
+        }
+
+        kill(&mut self.child)
+            .with_context(|| format!("Failed to kill command '{}'", self.command))
+            .map_err(SysxError::AnyhowError)?;
+        self.wait().map(|_| ())
+    }
+
+    /// Asks the child to exit (`SIGTERM` on Unix) and kills it after the grace period.
+    pub fn terminate(&mut self) -> Result<ExitStatus> {
+        if let Some(status) = self.try_wait()? {
+            return Ok(status);
+        }
+
+        let status = terminate(&mut self.child, self.kill_grace)
+            .with_context(|| format!("Failed to terminate command '{}'", self.command))
+            .map_err(SysxError::AnyhowError)?;
+        self.status = Some(status);
+        Ok(status)
+    }
+
+    /// Sends a raw signal (e.g. `libc::SIGHUP`) to the child and its process group.
+    #[cfg(unix)]
+    pub fn signal(&mut self, signal: i32) -> Result<()> {
+        if self.try_wait()?.is_some() {
+            return Err(SysxError::AnyhowError(anyhow::anyhow!(
+                "Command '{}' has already exited",
+                self.command
+            )));
+        }
+
+        super::child::send_signal(&self.child, signal)
+            .with_context(|| format!("Failed to signal command '{}'", self.command))
+            .map_err(SysxError::AnyhowError)
+    }
+
+    /// Returns stdout produced since the previous call (empty if stdout is not piped).
+    pub fn read_stdout(&mut self) -> Vec<u8> {
+        read_new(&self.stdout, &mut self.stdout_pos)
+    }
+
+    /// Returns stderr produced since the previous call (empty if stderr is not piped).
+    pub fn read_stderr(&mut self) -> Vec<u8> {
+        read_new(&self.stderr, &mut self.stderr_pos)
+    }
+}
+
+fn read_new(pipe: &Option<CapturedPipe>, pos: &mut usize) -> Vec<u8> {
+    let new = pipe
+        .as_ref()
+        .map(|pipe| pipe.read_from(*pos))
+        .unwrap_or_default();
+    *pos += new.len();
+    new
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        if self.on_drop == DropBehavior::Kill && matches!(self.try_wait(), Ok(None)) {
+            let _ = kill(&mut self.child);
+            let _ = self.child.wait();
+        }
+    }
+}
+
+impl Cmd {
+    /// Starts the command in the background and returns a handle to it.
+    ///
+    /// The timeout setting is not applied; use `Process::wait_with_timeout` instead.
+    pub fn spawn(&self) -> Result<Process> {
+        let (mut child, writer) = self.spawn_child()?;
+
+        Ok(Process {
+            stdout: child.stdout.take().map(CapturedPipe::spawn),
+            stderr: child.stderr.take().map(CapturedPipe::spawn),
+            child,
+            command: self.command_line(),
+            stdout_pos: 0,
+            stderr_pos: 0,
+            writer,
+            status: None,
+            kill_grace: self.get_kill_grace(),
+            on_drop: DropBehavior::default(),
+        })
+    }
+}
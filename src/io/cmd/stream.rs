@@ -0,0 +1,246 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    process::{Child, ExitStatus, Output, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+
+use super::{
+    Cmd,
+    child::{kill, terminate},
+};
+use crate::{Result, SysxError};
+
+/// Standard stream a line was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamKind {
+    /// The child's standard output.
+    Stdout,
+    /// The child's standard error.
+    Stderr,
+}
+
+/// A single line of child output, without the trailing newline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// Stream the line came from.
+    pub stream: StreamKind,
+    /// Line contents, lossily decoded as UTF-8.
+    pub text:   String,
+}
+
+/// Raw line as sent by the reader threads, newline included.
+type RawLine = (StreamKind, Vec<u8>);
+
+/// Iterator over the output lines of a running command, in arrival order.
+///
+/// Iteration ends once both stdout and stderr are closed, or when the command's timeout
+/// expires; call `wait` for the exit status. Dropping the stream kills a child that is
+/// still running.
+pub struct LineStream {
+    child:      Child,
+    command:    String,
+    lines:      Receiver<RawLine>,
+    readers:    Vec<JoinHandle<()>>,
+    writer:     Option<JoinHandle<()>>,
+    timeout:    Option<Duration>,
+    deadline:   Option<Instant>,
+    kill_grace: Duration,
+    timed_out:  bool,
+}
+
+fn spawn_reader<R>(reader: R, stream: StreamKind, tx: Sender<RawLine>) -> JoinHandle<()>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        loop {
+            let mut buf = Vec::new();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if tx.send((stream, buf)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn to_line((stream, raw): RawLine) -> Line {
+    let mut text = String::from_utf8_lossy(&raw).into_owned();
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+    Line { stream, text }
+}
+
+impl LineStream {
+    /// Returns the OS process id of the child.
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Discards any remaining output and waits for the child to exit.
+    ///
+    /// Returns `SysxError::CommandTimeout` if the timeout expires first; its captured output
+    /// only holds lines that were not already yielded.
+    pub fn wait(mut self) -> Result<ExitStatus> {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        while let Some((stream, raw)) = self.recv() {
+            match stream {
+                StreamKind::Stdout => stdout.extend_from_slice(&raw),
+                StreamKind::Stderr => stderr.extend_from_slice(&raw),
+            }
+        }
+        self.finish(&mut stdout, &mut stderr)
+    }
+
+    /// Receives the next raw line, giving up once the deadline passes.
+    fn recv(&mut self) -> Option<RawLine> {
+        if self.timed_out {
+            return None;
+        }
+        let Some(deadline) = self.deadline else {
+            return self.lines.recv().ok();
+        };
+        match self
+            .lines
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        {
+            Ok(raw) => Some(raw),
+            Err(RecvTimeoutError::Disconnected) => None,
+            Err(RecvTimeoutError::Timeout) => {
+                self.timed_out = true;
+                None
+            }
+        }
+    }
+
+    /// Reaps the child; after a timeout it is terminated first and `stdout`/`stderr` are
+    /// moved into the `CommandTimeout` error.
+    fn finish(&mut self, stdout: &mut Vec<u8>, stderr: &mut Vec<u8>) -> Result<ExitStatus> {
+        if self.timed_out
+            && let Some(timeout) = self.timeout
+        {
+            terminate(&mut self.child, self.kill_grace)
+                .with_context(|| format!("Failed to kill command '{}'", self.command))
+                .map_err(SysxError::AnyhowError)?;
+            return Err(SysxError::CommandTimeout {
+                command: self.command.clone(),
+                timeout,
+                stdout: std::mem::take(stdout),
+                stderr: std::mem::take(stderr),
+            });
+        }
+
+        for reader in self.readers.drain(..) {
+            let _ = reader.join();
+        }
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+
+        self.child
+            .wait()
+            .with_context(|| format!("Failed to wait for command '{}'", self.command))
+            .map_err(SysxError::AnyhowError)
+    }
+}
+
+impl Iterator for LineStream {
+    type Item = Line;
+
+    fn next(&mut self) -> Option<Line> {
+        self.recv().map(to_line)
+    }
+}
+
+impl Drop for LineStream {
+    fn drop(&mut self) {
+        if matches!(self.child.try_wait(), Ok(None)) {
+            let _ = kill(&mut self.child);
+        }
+        let _ = self.child.wait();
+    }
+}
+
+impl Cmd {
+    /// Spawns the command and returns an iterator over its output lines as they arrive.
+    ///
+    /// Stdout and stderr are always captured. With a timeout set, iteration stops when it
+    /// expires and `LineStream::wait` returns `SysxError::CommandTimeout`.
+    pub fn stream(&self) -> Result<LineStream> {
+        let (mut child, writer) = self.spawn_child_with(|command| {
+            command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        })?;
+
+        let (tx, rx) = mpsc::channel();
+        let mut readers = Vec::with_capacity(2);
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(spawn_reader(stdout, StreamKind::Stdout, tx.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(spawn_reader(stderr, StreamKind::Stderr, tx));
+        }
+
+        let timeout = self.get_timeout();
+        Ok(LineStream {
+            child,
+            command: self.command_line(),
+            lines: rx,
+            readers,
+            writer,
+            timeout,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+            kill_grace: self.get_kill_grace(),
+            timed_out: false,
+        })
+    }
+
+    /// Runs the command, calling `on_line` for every output line as it arrives.
+    ///
+    /// Returns the complete `Output` once the command has exited, or
+    /// `SysxError::CommandTimeout` with the output so far if the timeout expires.
+    pub fn for_each_line<F: FnMut(&Line)>(&self, mut on_line: F) -> Result<Output> {
+        let mut stream = self.stream()?;
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        while let Some(raw) = stream.recv() {
+            match raw.0 {
+                StreamKind::Stdout => stdout.extend_from_slice(&raw.1),
+                StreamKind::Stderr => stderr.extend_from_slice(&raw.1),
+            }
+            on_line(&to_line(raw));
+        }
+
+        let status = stream.finish(&mut stdout, &mut stderr)?;
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// Runs the command, echoing its output to the terminal live, and returns the full `Output`.
+    pub fn tee(&self) -> Result<Output> {
+        self.for_each_line(|line| match line.stream {
+            StreamKind::Stdout => {
+                let mut out = std::io::stdout().lock();
+                let _ = writeln!(out, "{}", line.text);
+                let _ = out.flush();
+            }
+            StreamKind::Stderr => eprintln!("{}", line.text),
+        })
+    }
+}
@@ -1,3 +1,15 @@
+pub mod cli;
+pub mod dirs;
+pub mod dotenv;
+pub mod scoped;
+pub mod vars;
+
+pub use cli::*;
+pub use dirs::*;
+pub use dotenv::*;
+pub use scoped::*;
+pub use vars::*;
+
 /// Returns a vector of command-line arguments, including the program name.
 pub fn get_full_args() -> Vec<String> {
     std::env::args().collect()
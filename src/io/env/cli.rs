@@ -0,0 +1,494 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use super::get_args;
+use crate::{
+    Result,
+    SysxError,
+    io::log::{Color, Colorize},
+    style,
+    types::checker::simplify_type,
+};
+
+/// Kind of a declared command-line argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgKind {
+    Flag,
+    Option,
+    Positional,
+}
+
+/// Declaration of a single flag, option or positional argument.
+#[derive(Debug, Clone)]
+pub struct Arg {
+    name:       String,
+    kind:       ArgKind,
+    short:      Option<char>,
+    long:       Option<String>,
+    help:       String,
+    value_name: String,
+    default:    Option<String>,
+    required:   bool,
+    multiple:   bool,
+}
+
+impl Arg {
+    fn new(name: &str, kind: ArgKind) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            short: None,
+            long: (kind != ArgKind::Positional).then(|| name.to_string()),
+            help: String::new(),
+            value_name: name.to_uppercase(),
+            default: None,
+            required: false,
+            multiple: false,
+        }
+    }
+
+    /// Boolean switch such as `--verbose`; may be repeated to count occurrences.
+    pub fn flag(name: &str) -> Self {
+        Self::new(name, ArgKind::Flag)
+    }
+
+    /// Option taking a value: `--name value`, `--name=value`, `-n value` or `-nvalue`.
+    pub fn option(name: &str) -> Self {
+        Self::new(name, ArgKind::Option)
+    }
+
+    /// Positional argument, matched in declaration order.
+    pub fn positional(name: &str) -> Self {
+        Self::new(name, ArgKind::Positional)
+    }
+
+    /// Sets the short form (`-v`).
+    pub fn short(mut self, short: char) -> Self {
+        self.short = Some(short);
+        self
+    }
+
+    /// Overrides the long form (defaults to the argument name).
+    pub fn long(mut self, long: &str) -> Self {
+        self.long = Some(long.to_string());
+        self
+    }
+
+    /// Sets the description shown in `--help`.
+    pub fn help(mut self, help: &str) -> Self {
+        self.help = help.to_string();
+        self
+    }
+
+    /// Sets the placeholder shown for the value in `--help`.
+    pub fn value_name(mut self, value_name: &str) -> Self {
+        self.value_name = value_name.to_string();
+        self
+    }
+
+    /// Value used when the argument is absent.
+    pub fn default(mut self, value: &str) -> Self {
+        self.default = Some(value.to_string());
+        self
+    }
+
+    /// Makes the argument mandatory.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Collects every occurrence (options) or all remaining values (positionals).
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Switch and placeholder as shown in `--help`, e.g. `-o, --output <FILE>`.
+    fn usage(&self) -> String {
+        let value = match (self.kind, self.multiple) {
+            (ArgKind::Flag, _) => String::new(),
+            (ArgKind::Positional, false) => return format!("<{}>", self.name),
+            (ArgKind::Positional, true) => return format!("<{}>...", self.name),
+            (ArgKind::Option, _) => format!(" <{}>", self.value_name),
+        };
+
+        let short = self
+            .short
+            .map(|c| format!("-{c}"))
+            .unwrap_or_else(|| "  ".into());
+        match (&self.long, self.short) {
+            (Some(long), Some(_)) => format!("{short}, --{long}{value}"),
+            (Some(long), None) => format!("    --{long}{value}"),
+            (None, _) => format!("{short}{value}"),
+        }
+    }
+}
+
+/// Values collected by `ArgParser::parse`.
+#[derive(Debug, Clone, Default)]
+pub struct Matches {
+    flags:      HashMap<String, usize>,
+    values:     HashMap<String, Vec<String>>,
+    subcommand: Option<(String, Box<Matches>)>,
+}
+
+impl Matches {
+    /// Returns `true` if the flag was given at least once.
+    pub fn flag(&self, name: &str) -> bool {
+        self.count(name) > 0
+    }
+
+    /// Returns how many times the flag was given.
+    pub fn count(&self, name: &str) -> usize {
+        self.flags.get(name).copied().unwrap_or(0)
+    }
+
+    /// Returns the last value of an option or positional argument.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values
+            .get(name)
+            .and_then(|v| v.last())
+            .map(String::as_str)
+    }
+
+    /// Returns every value of an option or positional argument.
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        self.values
+            .get(name)
+            .map(|v| v.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Parses the last value of `name` as `T`.
+    pub fn get<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.value(name)
+            .map(|value| parse_value(name, value))
+            .transpose()
+    }
+
+    /// Parses every value of `name` as `T`.
+    pub fn get_all<T>(&self, name: &str) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.values(name)
+            .into_iter()
+            .map(|value| parse_value(name, value))
+            .collect()
+    }
+
+    /// Returns the selected subcommand and its matches.
+    pub fn subcommand(&self) -> Option<(&str, &Matches)> {
+        self.subcommand
+            .as_ref()
+            .map(|(name, matches)| (name.as_str(), matches.as_ref()))
+    }
+}
+
+fn parse_value<T>(name: &str, value: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|e: T::Err| SysxError::ValidationError {
+            expected: simplify_type(std::any::type_name::<T>())
+                .unwrap_or_else(|_| std::any::type_name::<T>().to_string()),
+            actual:   value.to_string(),
+            context:  Some(format!("argument '{name}': {e}")),
+        })
+}
+
+/// Declarative command-line parser with generated `--help`.
+#[derive(Debug, Clone)]
+pub struct ArgParser {
+    name:        String,
+    about:       String,
+    args:        Vec<Arg>,
+    subcommands: Vec<ArgParser>,
+}
+
+impl ArgParser {
+    /// Creates a parser for a program (or subcommand) called `name`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name:        name.to_string(),
+            about:       String::new(),
+            args:        Vec::new(),
+            subcommands: Vec::new(),
+        }
+    }
+
+    /// Sets the description shown at the top of `--help`.
+    pub fn about(mut self, about: &str) -> Self {
+        self.about = about.to_string();
+        self
+    }
+
+    /// Declares an argument.
+    pub fn arg(mut self, arg: Arg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// Declares a subcommand; everything after its name is parsed by `parser`.
+    pub fn subcommand(mut self, parser: ArgParser) -> Self {
+        self.subcommands.push(parser);
+        self
+    }
+
+    /// Parses the process arguments; prints help or errors and exits on failure.
+    pub fn parse_env(&self) -> Matches {
+        match self.parse(get_args()) {
+            Ok(matches) => matches,
+            Err(SysxError::HelpRequested(help)) => {
+                println!("{help}");
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("{} {e}", style!("error:", Color::Red, bold));
+                eprintln!("Try '{} --help' for more information.", self.name);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    /// Parses `args` (without the program name).
+    ///
+    /// Returns `SysxError::HelpRequested` with the help text on `-h` / `--help`.
+    pub fn parse<I, S>(&self, args: I) -> Result<Matches>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.parse_from(
+            args.into_iter()
+                .map(Into::into)
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    fn parse_from(&self, mut args: std::vec::IntoIter<String>) -> Result<Matches> {
+        let positionals: Vec<&Arg> = self
+            .args
+            .iter()
+            .filter(|a| a.kind == ArgKind::Positional)
+            .collect();
+        let mut matches = Matches::default();
+        let mut next_positional = 0;
+        let mut only_positional = false;
+
+        while let Some(arg) = args.next() {
+            if only_positional || arg == "-" || !arg.starts_with('-') {
+                if !only_positional
+                    && let Some(sub) = self.subcommands.iter().find(|s| s.name == arg)
+                {
+                    matches.subcommand = Some((arg, Box::new(sub.parse_from(args)?)));
+                    break;
+                }
+
+                let Some(decl) = positionals.get(next_positional) else {
+                    return Err(SysxError::InvalidSyntax(format!(
+                        "Unexpected argument '{arg}'"
+                    )));
+                };
+                push_value(&mut matches, decl, arg);
+                if !decl.multiple {
+                    next_positional += 1;
+                }
+            } else if arg == "--" {
+                only_positional = true;
+            } else if let Some(long) = arg.strip_prefix("--") {
+                let (name, inline) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None),
+                };
+                if name == "help" && self.find_long(name).is_none() {
+                    return Err(SysxError::HelpRequested(self.help()));
+                }
+
+                let decl = self.find_long(name).ok_or_else(|| {
+                    SysxError::InvalidSyntax(format!("Unknown option '--{name}'"))
+                })?;
+                match (decl.kind, inline) {
+                    (ArgKind::Flag, Some(_)) => {
+                        return Err(SysxError::InvalidSyntax(format!(
+                            "Flag '--{name}' does not take a value"
+                        )));
+                    }
+                    (ArgKind::Flag, None) => {
+                        *matches.flags.entry(decl.name.clone()).or_default() += 1
+                    }
+                    (_, Some(value)) => push_value(&mut matches, decl, value),
+                    (_, None) => {
+                        let value = args.next().ok_or_else(|| missing_value(decl))?;
+                        push_value(&mut matches, decl, value);
+                    }
+                }
+            } else {
+                let cluster = &arg[1..];
+                for (i, c) in cluster.char_indices() {
+                    if c == 'h' && self.find_short(c).is_none() {
+                        return Err(SysxError::HelpRequested(self.help()));
+                    }
+
+                    let decl = self.find_short(c).ok_or_else(|| {
+                        SysxError::InvalidSyntax(format!("Unknown option '-{c}'"))
+                    })?;
+                    if decl.kind == ArgKind::Flag {
+                        *matches.flags.entry(decl.name.clone()).or_default() += 1;
+                        continue;
+                    }
+
+                    let rest = &cluster[i + c.len_utf8()..];
+                    let value = if rest.is_empty() {
+                        args.next().ok_or_else(|| missing_value(decl))?
+                    } else {
+                        rest.to_string()
+                    };
+                    push_value(&mut matches, decl, value);
+                    break;
+                }
+            }
+        }
+
+        for decl in self.args.iter().filter(|a| a.kind != ArgKind::Flag) {
+            if matches.values.contains_key(&decl.name) {
+                continue;
+            }
+            if let Some(default) = &decl.default {
+                matches
+                    .values
+                    .insert(decl.name.clone(), vec![default.clone()]);
+            } else if decl.required {
+                return Err(SysxError::InvalidSyntax(format!(
+                    "Missing required argument '{}'",
+                    decl.usage().trim()
+                )));
+            }
+        }
+
+        Ok(matches)
+    }
+
+    fn find_long(&self, name: &str) -> Option<&Arg> {
+        self.args
+            .iter()
+            .find(|a| a.kind != ArgKind::Positional && a.long.as_deref() == Some(name))
+    }
+
+    fn find_short(&self, short: char) -> Option<&Arg> {
+        self.args
+            .iter()
+            .find(|a| a.kind != ArgKind::Positional && a.short == Some(short))
+    }
+
+    /// Renders the `--help` text.
+    pub fn help(&self) -> String {
+        let mut out = String::new();
+        let heading = |text: &str| style!(text, Color::Yellow, bold).to_string();
+
+        if self.about.is_empty() {
+            out.push_str(&format!(
+                "{}\n\n",
+                style!(self.name.as_str(), Color::Green, bold)
+            ));
+        } else {
+            out.push_str(&format!(
+                "{} - {}\n\n",
+                style!(self.name.as_str(), Color::Green, bold),
+                self.about
+            ));
+        }
+
+        let positionals: Vec<&Arg> = self
+            .args
+            .iter()
+            .filter(|a| a.kind == ArgKind::Positional)
+            .collect();
+        let mut usage = format!("{} [OPTIONS]", self.name);
+        for arg in &positionals {
+            let text = arg.usage();
+            if arg.required {
+                usage.push_str(&format!(" {text}"));
+            } else {
+                usage.push_str(&format!(" [{text}]"));
+            }
+        }
+        if !self.subcommands.is_empty() {
+            usage.push_str(" [COMMAND]");
+        }
+        out.push_str(&format!("{} {usage}\n", heading("Usage:")));
+
+        let help_row = Arg::flag("help").short('h').help("Print help");
+        let options: Vec<&Arg> = self
+            .args
+            .iter()
+            .filter(|a| a.kind != ArgKind::Positional)
+            .chain(std::iter::once(&help_row))
+            .collect();
+
+        let sections: [(&str, Vec<(String, String)>); 3] = [
+            (
+                "Arguments:",
+                positionals
+                    .iter()
+                    .map(|a| (a.usage(), describe(a)))
+                    .collect(),
+            ),
+            (
+                "Options:",
+                options.iter().map(|a| (a.usage(), describe(a))).collect(),
+            ),
+            (
+                "Commands:",
+                self.subcommands
+                    .iter()
+                    .map(|s| (s.name.clone(), s.about.clone()))
+                    .collect(),
+            ),
+        ];
+
+        for (title, rows) in sections {
+            if rows.is_empty() {
+                continue;
+            }
+            let width = rows.iter().map(|(left, _)| left.len()).max().unwrap_or(0);
+            out.push_str(&format!("\n{}\n", heading(title)));
+            for (left, right) in rows {
+                let padding = " ".repeat(width - left.len() + 2);
+                out.push_str(&format!("  {}{padding}{right}\n", left.as_str().cyan()));
+            }
+        }
+
+        out.trim_end().to_string()
+    }
+}
+
+fn describe(arg: &Arg) -> String {
+    match &arg.default {
+        Some(default) => format!("{} [default: {default}]", arg.help)
+            .trim_start()
+            .to_string(),
+        None => arg.help.clone(),
+    }
+}
+
+fn push_value(matches: &mut Matches, decl: &Arg, value: String) {
+    let values = matches.values.entry(decl.name.clone()).or_default();
+    if !decl.multiple {
+        values.clear();
+    }
+    values.push(value);
+}
+
+fn missing_value(decl: &Arg) -> SysxError {
+    SysxError::InvalidSyntax(format!("Option '{}' requires a value", decl.usage().trim()))
+}
@@ -0,0 +1,153 @@
+use std::{
+    env,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use crate::{Result, SysxError};
+
+/// Reads a directory variable, ignoring it unless it holds an absolute path
+/// (as the XDG Base Directory spec requires).
+fn absolute_var(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+/// Resolves `xdg_var`, then the platform fallback, then `home/<relative>`.
+fn base_dir(xdg_var: &str, windows_var: &str, relative: &str) -> Result<PathBuf> {
+    if let Some(path) = absolute_var(xdg_var) {
+        return Ok(path);
+    }
+    if cfg!(windows)
+        && let Some(path) = absolute_var(windows_var)
+    {
+        return Ok(path);
+    }
+    Ok(home_dir()?.join(relative))
+}
+
+/// Returns the current user's home directory (`HOME`, or `USERPROFILE` on Windows).
+///
+/// Returns `SysxError::EnvVarNotFound` if it cannot be determined.
+pub fn home_dir() -> Result<PathBuf> {
+    let name = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    absolute_var(name).ok_or_else(|| SysxError::EnvVarNotFound(name.to_string()))
+}
+
+/// Returns the user config directory: `$XDG_CONFIG_HOME`, else `~/.config`
+/// (`%APPDATA%` on Windows).
+pub fn config_dir() -> Result<PathBuf> {
+    base_dir("XDG_CONFIG_HOME", "APPDATA", ".config")
+}
+
+/// Returns the user data directory: `$XDG_DATA_HOME`, else `~/.local/share`
+/// (`%APPDATA%` on Windows).
+pub fn data_dir() -> Result<PathBuf> {
+    base_dir("XDG_DATA_HOME", "APPDATA", ".local/share")
+}
+
+/// Returns the user cache directory: `$XDG_CACHE_HOME`, else `~/.cache`
+/// (`%LOCALAPPDATA%` on Windows).
+pub fn cache_dir() -> Result<PathBuf> {
+    base_dir("XDG_CACHE_HOME", "LOCALAPPDATA", ".cache")
+}
+
+/// Returns the user state directory: `$XDG_STATE_HOME`, else `~/.local/state`
+/// (`%LOCALAPPDATA%` on Windows).
+pub fn state_dir() -> Result<PathBuf> {
+    base_dir("XDG_STATE_HOME", "LOCALAPPDATA", ".local/state")
+}
+
+/// Returns `$XDG_RUNTIME_DIR`. The spec defines no fallback, so this is `None` when unset.
+pub fn runtime_dir() -> Option<PathBuf> {
+    absolute_var("XDG_RUNTIME_DIR")
+}
+
+/// Returns the directory for temporary files (`TMPDIR`, `TEMP`, or the system default).
+pub fn temp_dir() -> PathBuf {
+    env::temp_dir()
+}
+
+/// Per-application directories: `<base>/<app_name>` for each base directory.
+///
+/// ```no_run
+/// use sysx::io::env::AppDirs;
+///
+/// let dirs = AppDirs::new("mytool").unwrap().create(true);
+/// let config = dirs.config().unwrap().join("config.toml");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppDirs {
+    name:   String,
+    create: bool,
+}
+
+impl AppDirs {
+    /// Creates app directories named `name`, which must be a single path component.
+    pub fn new(name: &str) -> Result<Self> {
+        let mut components = Path::new(name).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => Ok(Self {
+                name:   name.to_string(),
+                create: false,
+            }),
+            _ => Err(SysxError::InvalidSyntax(format!(
+                "Invalid application directory name: '{name}'"
+            ))),
+        }
+    }
+
+    /// Creates each directory (and its parents) when it is requested.
+    pub fn create(mut self, create: bool) -> Self {
+        self.create = create;
+        self
+    }
+
+    /// Returns the application name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn resolve(&self, base: PathBuf) -> Result<PathBuf> {
+        let path = base.join(&self.name);
+        if self.create {
+            fs::create_dir_all(&path)
+                .with_context(|| format!("Failed to create '{}'", path.display()))
+                .map_err(SysxError::AnyhowError)?;
+        }
+        Ok(path)
+    }
+
+    /// Returns `config_dir()/<name>`.
+    pub fn config(&self) -> Result<PathBuf> {
+        self.resolve(config_dir()?)
+    }
+
+    /// Returns `data_dir()/<name>`.
+    pub fn data(&self) -> Result<PathBuf> {
+        self.resolve(data_dir()?)
+    }
+
+    /// Returns `cache_dir()/<name>`.
+    pub fn cache(&self) -> Result<PathBuf> {
+        self.resolve(cache_dir()?)
+    }
+
+    /// Returns `state_dir()/<name>`.
+    pub fn state(&self) -> Result<PathBuf> {
+        self.resolve(state_dir()?)
+    }
+
+    /// Returns `runtime_dir()/<name>`, or `None` if `XDG_RUNTIME_DIR` is unset.
+    pub fn runtime(&self) -> Result<Option<PathBuf>> {
+        runtime_dir().map(|base| self.resolve(base)).transpose()
+    }
+
+    /// Returns `temp_dir()/<name>`.
+    pub fn temp(&self) -> Result<PathBuf> {
+        self.resolve(temp_dir())
+    }
+}
@@ -0,0 +1,338 @@
+use std::{collections::BTreeMap, env, fs, path::Path};
+
+use anyhow::Context;
+
+use super::env_lock;
+use crate::{Result, SysxError};
+
+/// Which value wins when a `.env` key is already set in the process environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Precedence {
+    /// Values from the file win (also when resolving `${VAR}`).
+    File,
+    /// Values already in the process environment win.
+    Process,
+}
+
+/// Character cursor tracking line and column for error messages.
+struct Cursor {
+    chars:  Vec<char>,
+    pos:    usize,
+    line:   usize,
+    column: usize,
+}
+
+impl Cursor {
+    fn new(source: &str) -> Self {
+        Self {
+            chars:  source.chars().collect(),
+            pos:    0,
+            line:   1,
+            column: 1,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_blanks(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.bump();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.bump() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn error(&self, message: &str) -> SysxError {
+        SysxError::InvalidSyntax(format!(
+            "line {}, column {}: {message}",
+            self.line, self.column
+        ))
+    }
+}
+
+/// Parser state: values read so far plus the lookup rule for interpolation.
+struct Parser {
+    cursor:     Cursor,
+    values:     BTreeMap<String, String>,
+    precedence: Precedence,
+}
+
+impl Parser {
+    fn lookup(&self, name: &str) -> Option<String> {
+        let from_file = || self.values.get(name).cloned();
+        let from_env = || env::var(name).ok();
+        match self.precedence {
+            Precedence::File => from_file().or_else(from_env),
+            Precedence::Process => from_env().or_else(from_file),
+        }
+    }
+
+    fn parse(mut self) -> Result<BTreeMap<String, String>> {
+        loop {
+            while matches!(self.cursor.peek(), Some(c) if c.is_whitespace()) {
+                self.cursor.bump();
+            }
+            match self.cursor.peek() {
+                None => break,
+                Some('#') => self.cursor.skip_line(),
+                Some(_) => {
+                    let (key, value) = self.parse_entry()?;
+                    self.values.insert(key, value);
+                }
+            }
+        }
+        Ok(self.values)
+    }
+
+    fn parse_key(&mut self) -> Result<String> {
+        let mut key = String::new();
+        while let Some(c) = self.cursor.peek() {
+            let valid = c == '_'
+                || c.is_ascii_alphabetic()
+                || (!key.is_empty() && (c.is_ascii_digit() || c == '.'));
+            if !valid {
+                break;
+            }
+            key.push(c);
+            self.cursor.bump();
+        }
+
+        if key.is_empty() {
+            return Err(self.cursor.error("expected a variable name"));
+        }
+        Ok(key)
+    }
+
+    fn parse_entry(&mut self) -> Result<(String, String)> {
+        let mut key = self.parse_key()?;
+        if key == "export" && matches!(self.cursor.peek(), Some(' ' | '\t')) {
+            self.cursor.skip_blanks();
+            key = self.parse_key()?;
+        }
+
+        self.cursor.skip_blanks();
+        if self.cursor.peek() != Some('=') {
+            return Err(self.cursor.error(&format!("expected '=' after '{key}'")));
+        }
+        self.cursor.bump();
+        self.cursor.skip_blanks();
+
+        let value = match self.cursor.peek() {
+            Some('\'') => self.parse_single_quoted()?,
+            Some('"') => self.parse_double_quoted()?,
+            _ => return Ok((key, self.parse_unquoted()?)),
+        };
+
+        self.cursor.skip_blanks();
+        match self.cursor.peek() {
+            None | Some('\n') => {}
+            Some('\r') if self.cursor.peek_at(1) == Some('\n') => {}
+            Some('#') => self.cursor.skip_line(),
+            Some(_) => return Err(self.cursor.error("unexpected character after quoted value")),
+        }
+        Ok((key, value))
+    }
+
+    fn parse_single_quoted(&mut self) -> Result<String> {
+        self.cursor.bump();
+        let mut value = String::new();
+        loop {
+            match self.cursor.bump() {
+                Some('\'') => return Ok(value),
+                Some(c) => value.push(c),
+                None => return Err(self.cursor.error("unterminated single-quoted value")),
+            }
+        }
+    }
+
+    fn parse_double_quoted(&mut self) -> Result<String> {
+        self.cursor.bump();
+        let mut value = String::new();
+        loop {
+            match self.cursor.peek() {
+                Some('"') => {
+                    self.cursor.bump();
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.cursor.bump();
+                    let escaped = match self.cursor.bump() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some(c @ ('"' | '\\' | '$' | '\'')) => c,
+                        Some(c) => {
+                            value.push('\\');
+                            c
+                        }
+                        None => break,
+                    };
+                    value.push(escaped);
+                }
+                Some('$') if self.cursor.peek_at(1) == Some('{') => {
+                    value.push_str(&self.parse_interpolation()?)
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.cursor.bump();
+                }
+                None => break,
+            }
+        }
+        Err(self.cursor.error("unterminated double-quoted value"))
+    }
+
+    fn parse_unquoted(&mut self) -> Result<String> {
+        let mut value = String::new();
+        while let Some(c) = self.cursor.peek() {
+            match c {
+                '\n' => break,
+                '#' if value.is_empty() || value.ends_with([' ', '\t']) => {
+                    self.cursor.skip_line();
+                    break;
+                }
+                '$' if self.cursor.peek_at(1) == Some('{') => {
+                    value.push_str(&self.parse_interpolation()?)
+                }
+                _ => {
+                    value.push(c);
+                    self.cursor.bump();
+                }
+            }
+        }
+        Ok(value.trim_end().to_string())
+    }
+
+    /// Parses `${VAR}` or `${VAR:-default}` at the cursor and returns its value.
+    fn parse_interpolation(&mut self) -> Result<String> {
+        self.cursor.bump();
+        self.cursor.bump();
+        let name = self.parse_key()?;
+
+        let default = if self.cursor.peek() == Some(':') && self.cursor.peek_at(1) == Some('-') {
+            self.cursor.bump();
+            self.cursor.bump();
+            let mut default = String::new();
+            while let Some(c) = self.cursor.peek() {
+                if c == '}' || c == '\n' {
+                    break;
+                }
+                default.push(c);
+                self.cursor.bump();
+            }
+            Some(default)
+        } else {
+            None
+        };
+
+        if self.cursor.bump() != Some('}') {
+            return Err(self
+                .cursor
+                .error(&format!("unterminated '${{{name}' expression")));
+        }
+
+        let value = self
+            .lookup(&name)
+            .filter(|v| !v.is_empty() || default.is_none());
+        Ok(value.or(default).unwrap_or_default())
+    }
+}
+
+fn parse_with(source: &str, precedence: Precedence) -> Result<BTreeMap<String, String>> {
+    Parser {
+        cursor: Cursor::new(source),
+        values: BTreeMap::new(),
+        precedence,
+    }
+    .parse()
+}
+
+fn read_with(path: &Path, precedence: Precedence) -> Result<BTreeMap<String, String>> {
+    let source = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read '{}'", path.display()))
+        .map_err(SysxError::AnyhowError)?;
+
+    parse_with(&source, precedence).map_err(|e| match e {
+        SysxError::InvalidSyntax(msg) => {
+            SysxError::InvalidSyntax(format!("{}: {msg}", path.display()))
+        }
+        other => other,
+    })
+}
+
+/// Parses `.env` contents without touching the process environment.
+///
+/// Supports `KEY=value`, `export KEY=value`, `#` comments, single quotes (literal),
+/// double quotes (with `\n`, `\t`, `\"`, `\\`, `\$` escapes), and `${VAR}` /
+/// `${VAR:-default}` interpolation from earlier keys or the process environment.
+pub fn parse_dotenv(source: &str) -> Result<BTreeMap<String, String>> {
+    parse_with(source, Precedence::File)
+}
+
+/// Reads and parses a `.env` file without touching the process environment.
+pub fn read_dotenv<P: AsRef<Path>>(path: P) -> Result<BTreeMap<String, String>> {
+    read_with(path.as_ref(), Precedence::File)
+}
+
+/// Loads a `.env` file into the process environment, keeping variables that are already set.
+///
+/// Returns the values parsed from the file. Call this early, before spawning threads
+/// that read the environment.
+///
+/// # Safety
+///
+/// This calls `std::env::set_var`. No other thread may read or write the environment
+/// during the call unless it holds `env_lock`, which this function takes.
+pub unsafe fn load_dotenv<P: AsRef<Path>>(path: P) -> Result<BTreeMap<String, String>> {
+    let _lock = env_lock();
+    let values = read_with(path.as_ref(), Precedence::Process)?;
+    for (key, value) in &values {
+        if env::var_os(key).is_none() {
+            // SAFETY: upheld by the caller; see the `# Safety` section.
+            unsafe { env::set_var(key, value) };
+        }
+    }
+    Ok(values)
+}
+
+/// Loads a `.env` file into the process environment, overwriting existing variables.
+///
+/// Returns the values parsed from the file. Call this early, before spawning threads
+/// that read the environment.
+///
+/// # Safety
+///
+/// Same contract as `load_dotenv`.
+pub unsafe fn load_dotenv_override<P: AsRef<Path>>(path: P) -> Result<BTreeMap<String, String>> {
+    let _lock = env_lock();
+    let values = read_with(path.as_ref(), Precedence::File)?;
+    for (key, value) in &values {
+        // SAFETY: upheld by the caller; see the `# Safety` section of `load_dotenv`.
+        unsafe { env::set_var(key, value) };
+    }
+    Ok(values)
+}
@@ -0,0 +1,163 @@
+use std::{
+    env,
+    ffi::{OsStr, OsString},
+    marker::PhantomData,
+    sync::{Condvar, Mutex},
+    thread::{self, ThreadId},
+};
+
+/// Owner and recursion depth of the environment lock.
+struct LockState {
+    owner: Option<ThreadId>,
+    depth: usize,
+}
+
+static ENV_LOCK: Mutex<LockState> = Mutex::new(LockState {
+    owner: None,
+    depth: 0,
+});
+static ENV_RELEASED: Condvar = Condvar::new();
+
+/// Holds the process-wide environment lock. See `env_lock`.
+#[must_use = "the lock is released as soon as the guard is dropped"]
+pub struct EnvLockGuard {
+    // The lock is owned by a thread, so the guard must stay on it.
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Locks the process environment against other `EnvGuard`s and `env_lock` callers.
+///
+/// The lock is reentrant: a thread that already holds it (for example through an
+/// `EnvGuard`) can lock it again. Hold it in tests that read variables set by other
+/// tests. A panic while locked does not poison it for later callers.
+pub fn env_lock() -> EnvLockGuard {
+    let me = thread::current().id();
+    let mut state = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        match state.owner {
+            None => state.owner = Some(me),
+            Some(owner) if owner != me => {
+                state = ENV_RELEASED.wait(state).unwrap_or_else(|e| e.into_inner());
+                continue;
+            }
+            Some(_) => {}
+        }
+        state.depth += 1;
+        return EnvLockGuard {
+            _not_send: PhantomData,
+        };
+    }
+}
+
+impl Drop for EnvLockGuard {
+    fn drop(&mut self) {
+        let mut state = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        state.depth -= 1;
+        if state.depth == 0 {
+            state.owner = None;
+            ENV_RELEASED.notify_one();
+        }
+    }
+}
+
+/// Sets and removes environment variables, restoring the previous values on drop.
+///
+/// Holds `env_lock` for its whole lifetime, so guards in parallel tests run one at a time.
+/// Guards may be nested on the same thread; they restore in reverse order.
+///
+/// ```no_run
+/// use sysx::io::env::{EnvGuard, get_var};
+///
+/// // SAFETY: no other thread touches the environment in this example.
+/// let _env = unsafe { EnvGuard::new() }.set("PORT", "8080").remove("DEBUG");
+/// assert_eq!(get_var("PORT").unwrap(), "8080");
+/// ```
+pub struct EnvGuard {
+    saved: Vec<(OsString, Option<OsString>)>,
+    _lock: EnvLockGuard,
+}
+
+impl EnvGuard {
+    /// Locks the environment without changing anything yet.
+    ///
+    /// # Safety
+    ///
+    /// The guard changes the environment with `std::env::set_var` and `remove_var`, both
+    /// on `set`/`remove` and when dropped. While it is alive, no other thread may read or
+    /// write the environment unless it also holds `env_lock`. That includes `get_var`,
+    /// `std::env::var`, spawning processes and C code calling `getenv`. See
+    /// `std::env::set_var` for details.
+    pub unsafe fn new() -> Self {
+        Self {
+            saved: Vec::new(),
+            _lock: env_lock(),
+        }
+    }
+
+    fn save(&mut self, key: &OsStr) {
+        self.saved.push((key.to_os_string(), env::var_os(key)));
+    }
+
+    /// Sets `key` to `value` until the guard is dropped.
+    pub fn set<K: AsRef<OsStr>, V: AsRef<OsStr>>(mut self, key: K, value: V) -> Self {
+        self.save(key.as_ref());
+        // SAFETY: upheld by the caller of `EnvGuard::new`.
+        unsafe { env::set_var(key, value) };
+        self
+    }
+
+    /// Removes `key` until the guard is dropped.
+    pub fn remove<K: AsRef<OsStr>>(mut self, key: K) -> Self {
+        self.save(key.as_ref());
+        // SAFETY: upheld by the caller of `EnvGuard::new`.
+        unsafe { env::remove_var(key) };
+        self
+    }
+
+    /// Applies a batch of changes: `Some(value)` sets the variable, `None` removes it.
+    pub fn vars<I, K, V>(self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Option<V>)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        vars.into_iter()
+            .fold(self, |guard, (key, value)| match value {
+                Some(value) => guard.set(key, value),
+                None => guard.remove(key),
+            })
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        // Newest first, so a key changed twice ends up with its original value.
+        for (key, value) in self.saved.drain(..).rev() {
+            // SAFETY: upheld by the caller of `EnvGuard::new`; the lock is still held
+            // until `_lock` drops.
+            unsafe {
+                match value {
+                    Some(value) => env::set_var(&key, value),
+                    None => env::remove_var(&key),
+                }
+            }
+        }
+    }
+}
+
+/// Runs `f` with the given variables set (`Some`) or removed (`None`), then restores them.
+///
+/// # Safety
+///
+/// Same contract as `EnvGuard::new`, for the duration of the call.
+pub unsafe fn with_env<I, K, V, R, F>(vars: I, f: F) -> R
+where
+    I: IntoIterator<Item = (K, Option<V>)>,
+    K: AsRef<OsStr>,
+    V: AsRef<OsStr>,
+    F: FnOnce() -> R,
+{
+    // SAFETY: forwarded to the caller.
+    let _guard = unsafe { EnvGuard::new() }.vars(vars);
+    f()
+}
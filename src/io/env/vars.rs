@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, env, fmt::Display, str::FromStr, time::Duration};
+
+use crate::{Result, SysxError, time::SleepTime, types::checker::simplify_type};
+
+/// Reads an environment variable.
+///
+/// Returns `SysxError::EnvVarNotFound` if it is unset.
+pub fn get_var(name: &str) -> Result<String> {
+    match env::var(name) {
+        Ok(value) => Ok(value),
+        Err(env::VarError::NotPresent) => Err(SysxError::EnvVarNotFound(name.to_string())),
+        Err(env::VarError::NotUnicode(_)) => Err(SysxError::InvalidSyntax(format!(
+            "Environment variable {name} is not valid Unicode"
+        ))),
+    }
+}
+
+/// Reads an environment variable, falling back to `default` if it is unset or not Unicode.
+pub fn get_var_or(name: &str, default: &str) -> String {
+    get_var(name).unwrap_or_else(|_| default.to_string())
+}
+
+/// Reads an environment variable and parses it as `T`.
+///
+/// Returns `SysxError::EnvVarNotFound` if unset, or `SysxError::ValidationError` naming
+/// the variable if the value does not parse.
+pub fn get_var_parsed<T>(name: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = get_var(name)?;
+    value
+        .trim()
+        .parse()
+        .map_err(|e: T::Err| invalid_var::<T>(name, &value, e))
+}
+
+fn invalid_var<T>(name: &str, value: &str, err: impl Display) -> SysxError {
+    SysxError::ValidationError {
+        expected: simplify_type(std::any::type_name::<T>())
+            .unwrap_or_else(|_| std::any::type_name::<T>().to_string()),
+        actual:   value.to_string(),
+        context:  Some(format!("environment variable {name}: {err}")),
+    }
+}
+
+/// Parses a boolean the way environment flags are usually written.
+///
+/// Accepts `1/true/yes/on` and `0/false/no/off` (case-insensitive); `None` otherwise.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Reads a boolean environment variable (`1/true/yes/on`, `0/false/no/off`).
+pub fn get_var_bool(name: &str) -> Result<bool> {
+    let value = get_var(name)?;
+    parse_bool(&value).ok_or_else(|| {
+        invalid_var::<bool>(
+            name,
+            &value,
+            "expected one of 1/true/yes/on or 0/false/no/off",
+        )
+    })
+}
+
+/// Reads a duration such as `"30s"` or `"250ms"`, using the `time::sleep` format.
+pub fn get_var_duration(name: &str) -> Result<Duration> {
+    let value = get_var(name)?;
+    value
+        .parse::<SleepTime>()
+        .map(SleepTime::to_duration)
+        .map_err(|e| invalid_var::<Duration>(name, &value, e))
+}
+
+/// Reads a comma-separated list, trimming items and skipping empty ones.
+pub fn get_var_list(name: &str) -> Result<Vec<String>> {
+    Ok(split_list(&get_var(name)?).map(str::to_string).collect())
+}
+
+/// Reads a comma-separated list and parses every item as `T`.
+pub fn get_var_list_parsed<T>(name: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = get_var(name)?;
+    split_list(&value)
+        .map(|item| {
+            item.parse()
+                .map_err(|e: T::Err| invalid_var::<T>(name, item, e))
+        })
+        .collect()
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Returns all environment variables with Unicode names and values, sorted by name.
+pub fn vars() -> BTreeMap<String, String> {
+    env::vars_os()
+        .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
+        .collect()
+}
+
+/// Returns environment variables whose names start with `prefix`, sorted by name.
+pub fn vars_with_prefix(prefix: &str) -> BTreeMap<String, String> {
+    vars()
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(prefix))
+        .collect()
+}
@@ -1,6 +1,36 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
 pub use chrono::Local;
 pub use colored::{Color, ColoredString, Colorize};
 
+use crate::SysxError;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub mod bridge;
+pub mod capture;
+pub mod format;
+pub mod logger;
+pub mod nonblocking;
+pub mod record;
+pub mod rotate;
+pub mod sink;
+pub mod span;
+pub mod theme;
+pub mod value;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub use bridge::*;
+pub use capture::*;
+pub use format::*;
+pub use logger::*;
+pub use nonblocking::*;
+pub use record::*;
+pub use rotate::*;
+pub use sink::*;
+pub use span::*;
+pub use theme::*;
+pub use value::*;
+
 /// Logging levels with associated styles.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogLevel {
@@ -28,67 +58,327 @@ impl LogLevel {
             LogLevel::Trace => Color::Cyan,
         }
     }
+
+    /// Returns the severity rank, from `Trace` (0) to `Fatal` (7).
+    pub fn severity(&self) -> u8 {
+        match self {
+            LogLevel::Trace => 0,
+            LogLevel::Debug => 1,
+            LogLevel::Info => 2,
+            LogLevel::Success => 3,
+            LogLevel::Warning => 4,
+            LogLevel::Error => 5,
+            LogLevel::Bug => 6,
+            LogLevel::Fatal => 7,
+        }
+    }
+
+    /// Returns the upper-case level name, e.g. `"WARNING"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "INFO",
+            LogLevel::Success => "SUCCESS",
+            LogLevel::Warning => "WARNING",
+            LogLevel::Error => "ERROR",
+            LogLevel::Bug => "BUG",
+            LogLevel::Fatal => "FATAL",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+}
+
+/// Levels are ordered by severity: `Trace < Debug < Info < Success < Warning < Error < Bug < Fatal`.
+impl Ord for LogLevel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.severity().cmp(&other.severity())
+    }
+}
+
+impl PartialOrd for LogLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Parses a level name case-insensitively. Also accepts the aliases `warn`, `err`,
+/// `dbg`, `ok` and `critical`.
+impl FromStr for LogLevel {
+    type Err = SysxError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" | "dbg" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "success" | "ok" => Ok(LogLevel::Success),
+            "warning" | "warn" => Ok(LogLevel::Warning),
+            "error" | "err" => Ok(LogLevel::Error),
+            "bug" => Ok(LogLevel::Bug),
+            "fatal" | "critical" => Ok(LogLevel::Fatal),
+            _ => Err(SysxError::InvalidSyntax(format!(
+                "Unknown log level: '{s}'"
+            ))),
+        }
+    }
 }
 
 /// Macro to convert a log level identifier (e.g., INFO) to a LogLevel enum value.
+///
+/// Resolved during macro expansion: accepts each level in UPPER, Title or lower case, plus
+/// the aliases `WARN`, `ERR`, `DBG`, `OK` and `CRITICAL` in the same three spellings.
+/// Anything else is a compile error, including other capitalisations such as `InFo`:
+///
+/// ```compile_fail
+/// let level = sysx::log_level!(WARNIN);
+/// ```
+///
+/// ```compile_fail
+/// let level = sysx::log_level!(InFo);
+/// ```
+///
+/// To accept any capitalisation, parse the name at runtime with `LogLevel::from_str`.
 #[macro_export]
 macro_rules! log_level {
-    ($level:ident) => {{
-        use $crate::io::log::LogLevel::*;
-        match stringify!($level).to_uppercase().as_str() {
-            "INFO" => Info,
-            "SUCCESS" => Success,
-            "WARNING" => Warning,
-            "ERROR" => Error,
-            "BUG" => Bug,
-            "FATAL" => Fatal,
-            "DEBUG" => Debug,
-            "TRACE" => Trace,
-            _ => panic!("Unknown log level: {}", stringify!($level)),
-        }
-    }};
+    (TRACE) => {
+        $crate::io::log::LogLevel::Trace
+    };
+    (Trace) => {
+        $crate::io::log::LogLevel::Trace
+    };
+    (trace) => {
+        $crate::io::log::LogLevel::Trace
+    };
+    (DEBUG) => {
+        $crate::io::log::LogLevel::Debug
+    };
+    (Debug) => {
+        $crate::io::log::LogLevel::Debug
+    };
+    (debug) => {
+        $crate::io::log::LogLevel::Debug
+    };
+    (DBG) => {
+        $crate::io::log::LogLevel::Debug
+    };
+    (Dbg) => {
+        $crate::io::log::LogLevel::Debug
+    };
+    (dbg) => {
+        $crate::io::log::LogLevel::Debug
+    };
+    (INFO) => {
+        $crate::io::log::LogLevel::Info
+    };
+    (Info) => {
+        $crate::io::log::LogLevel::Info
+    };
+    (info) => {
+        $crate::io::log::LogLevel::Info
+    };
+    (SUCCESS) => {
+        $crate::io::log::LogLevel::Success
+    };
+    (Success) => {
+        $crate::io::log::LogLevel::Success
+    };
+    (success) => {
+        $crate::io::log::LogLevel::Success
+    };
+    (OK) => {
+        $crate::io::log::LogLevel::Success
+    };
+    (Ok) => {
+        $crate::io::log::LogLevel::Success
+    };
+    (ok) => {
+        $crate::io::log::LogLevel::Success
+    };
+    (WARNING) => {
+        $crate::io::log::LogLevel::Warning
+    };
+    (Warning) => {
+        $crate::io::log::LogLevel::Warning
+    };
+    (warning) => {
+        $crate::io::log::LogLevel::Warning
+    };
+    (WARN) => {
+        $crate::io::log::LogLevel::Warning
+    };
+    (Warn) => {
+        $crate::io::log::LogLevel::Warning
+    };
+    (warn) => {
+        $crate::io::log::LogLevel::Warning
+    };
+    (ERROR) => {
+        $crate::io::log::LogLevel::Error
+    };
+    (Error) => {
+        $crate::io::log::LogLevel::Error
+    };
+    (error) => {
+        $crate::io::log::LogLevel::Error
+    };
+    (ERR) => {
+        $crate::io::log::LogLevel::Error
+    };
+    (Err) => {
+        $crate::io::log::LogLevel::Error
+    };
+    (err) => {
+        $crate::io::log::LogLevel::Error
+    };
+    (BUG) => {
+        $crate::io::log::LogLevel::Bug
+    };
+    (Bug) => {
+        $crate::io::log::LogLevel::Bug
+    };
+    (bug) => {
+        $crate::io::log::LogLevel::Bug
+    };
+    (FATAL) => {
+        $crate::io::log::LogLevel::Fatal
+    };
+    (Fatal) => {
+        $crate::io::log::LogLevel::Fatal
+    };
+    (fatal) => {
+        $crate::io::log::LogLevel::Fatal
+    };
+    (CRITICAL) => {
+        $crate::io::log::LogLevel::Fatal
+    };
+    (Critical) => {
+        $crate::io::log::LogLevel::Fatal
+    };
+    (critical) => {
+        $crate::io::log::LogLevel::Fatal
+    };
+    ($other:ident) => {
+        compile_error!(concat!(
+            "Unknown log level `",
+            stringify!($other),
+            "`; expected one of TRACE, DEBUG, INFO, SUCCESS, WARNING, ERROR, BUG, FATAL \
+             (or WARN, ERR, DBG, OK, CRITICAL)"
+        ))
+    };
 }
 pub use log_level;
 
 /// Primary logging macro with simplified syntax.
-/// Formats a log message with a specified level and text, and optional context.
+/// Formats a log message with a specified level and text, followed by an optional
+/// context string and/or structured fields after `;`:
+///
+/// ```no_run
+/// use sysx::log;
+///
+/// let peer = "10.0.0.7:443";
+/// log!(INFO, "connected to {}", peer);
+/// log!(ERROR, "request failed"; "while fetching /status");
+/// log!(INFO, "connected"; peer = peer, retries = 3);
+/// log!(WARNING, "slow reply"; "retrying"; elapsed = %"1.2s", status = ?Some(503));
+/// ```
+///
+/// Field values implement `ToValue`; `%expr` logs via `Display`, `?expr` via `Debug`.
 #[macro_export]
 macro_rules! log {
-    ($level:ident, $($msg:tt)*) => {
-        $crate::log_internal!(
-            $crate::log_level!($level),
-            format!($($msg)*),
-            None
-        )
+    ($level:ident, $fmt:literal $($rest:tt)*) => {
+        $crate::log!(@args $level [$fmt] $($rest)*)
+    };
+
+    // Collects format arguments one at a time, up to the first top-level `;`.
+    (@args $level:ident [$($msg:tt)+] $(,)?) => {
+        $crate::log!(@emit $level [$($msg)+] None, [])
+    };
+    (@args $level:ident [$($msg:tt)+] ; $($rest:tt)+) => {
+        $crate::log!(@extra $level [$($msg)+] $($rest)+)
+    };
+    (@args $level:ident [$($msg:tt)+] , $name:ident = $value:expr ; $($rest:tt)+) => {
+        $crate::log!(@args $level [$($msg)+, $name = $value] ; $($rest)+)
+    };
+    (@args $level:ident [$($msg:tt)+] , $name:ident = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::log!(@args $level [$($msg)+, $name = $value] $(, $($rest)*)?)
+    };
+    (@args $level:ident [$($msg:tt)+] , $arg:expr ; $($rest:tt)+) => {
+        $crate::log!(@args $level [$($msg)+, $arg] ; $($rest)+)
+    };
+    (@args $level:ident [$($msg:tt)+] , $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::log!(@args $level [$($msg)+, $arg] $(, $($rest)*)?)
+    };
+
+    (@extra $level:ident [$($msg:tt)+] $key:ident = $($fields:tt)+) => {
+        $crate::log!(@fields $level [$($msg)+] None, [] $key = $($fields)+)
+    };
+    (@extra $level:ident [$($msg:tt)+] $ctx:expr ; $($fields:tt)+) => {
+        $crate::log!(@fields $level [$($msg)+] Some($ctx.to_string()), [] $($fields)+)
+    };
+    (@extra $level:ident [$($msg:tt)+] $ctx:expr) => {
+        $crate::log!(@emit $level [$($msg)+] Some($ctx.to_string()), [])
     };
 
-    ($level:ident, $($msg:tt)*; $ctx:expr) => {
+    // Converts `key = value`, `key = %value` and `key = ?value` fields one at a time.
+    (@fields $level:ident [$($msg:tt)+] $ctx:expr, [$($out:expr),*]
+        $key:ident = % $value:expr $(, $($rest:tt)*)?) => {
+        $crate::log!(@fields $level [$($msg)+] $ctx, [$($out,)* (
+            stringify!($key).to_string(),
+            $crate::io::log::Value::Str(($value).to_string()),
+        )] $($($rest)*)?)
+    };
+    (@fields $level:ident [$($msg:tt)+] $ctx:expr, [$($out:expr),*]
+        $key:ident = ? $value:expr $(, $($rest:tt)*)?) => {
+        $crate::log!(@fields $level [$($msg)+] $ctx, [$($out,)* (
+            stringify!($key).to_string(),
+            $crate::io::log::Value::Str(format!("{:?}", $value)),
+        )] $($($rest)*)?)
+    };
+    (@fields $level:ident [$($msg:tt)+] $ctx:expr, [$($out:expr),*]
+        $key:ident = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::log!(@fields $level [$($msg)+] $ctx, [$($out,)* (
+            stringify!($key).to_string(),
+            $crate::io::log::ToValue::to_value(&$value),
+        )] $($($rest)*)?)
+    };
+    (@fields $level:ident [$($msg:tt)+] $ctx:expr, [$($out:expr),*]) => {
+        $crate::log!(@emit $level [$($msg)+] $ctx, [$($out),*])
+    };
+
+    (@emit $level:ident [$($msg:tt)+] $ctx:expr, [$($field:expr),*]) => {
         $crate::log_internal!(
             $crate::log_level!($level),
-            format!($($msg)*),
-            Some($ctx.to_string())
+            format!($($msg)+),
+            $ctx,
+            ::std::vec![$($field),*]
         )
     };
 }
 pub use log;
 
-/// Internal logging macro that handles the actual message output.
-/// Takes log level, formatted message, and optional context.
+/// Internal logging macro that hands the record to the global `Logger`.
+/// Takes log level, formatted message, optional context and optional fields; these are
+/// only evaluated if the level is enabled for the calling module.
 #[macro_export]
 macro_rules! log_internal {
-    ($level:expr, $msg:expr, $ctx:expr) => {{
-        let color = $level.style();
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-        let level_name = format!("{:?}", $level).to_uppercase();
-        let styled_msg = $crate::style!(format!("[{}] {}", level_name, $msg), color, bold);
-        let ctx_str = $ctx.map(|c: String| format!("\n  ↳ {}", c.dimmed()));
-
-        println!(
-            "{} {} {}",
-            timestamp.to_string().dimmed(),
-            styled_msg,
-            ctx_str.unwrap_or_default(),
-        );
+    ($level:expr, $msg:expr, $ctx:expr) => {
+        $crate::log_internal!($level, $msg, $ctx, ::std::vec::Vec::new())
+    };
+
+    ($level:expr, $msg:expr, $ctx:expr, $fields:expr) => {{
+        let level: $crate::io::log::LogLevel = $level;
+        if $crate::io::log::enabled(level, module_path!()) {
+            $crate::io::log::dispatch(
+                &$crate::io::log::Record::new(level, $msg, $ctx, module_path!(), file!(), line!())
+                    .with_fields($fields),
+            );
+        }
     }};
 }
 pub use log_internal;
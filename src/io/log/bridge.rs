@@ -0,0 +1,242 @@
+//! Adapters that route the `log` and `tracing` facades into the sysx `Logger`.
+//!
+//! Records keep their module path, file and line, and go through the global logger's
+//! filters and sinks, so output looks the same as `log!`. A `sysx_level` field holding a
+//! level name (e.g. `"fatal"`) overrides the mapped level, which is how the sysx-only
+//! `Success`, `Bug` and `Fatal` levels are reached from either facade.
+
+use super::{LogLevel, Record, Value, dispatch, enabled};
+
+/// Field that overrides the level mapped from the facade.
+pub const LEVEL_FIELD: &str = "sysx_level";
+
+/// Applies a `sysx_level` override and removes that field from the record.
+fn apply_level_override(record: &mut Record) {
+    if let Some(i) = record.fields.iter().position(|(k, _)| k == LEVEL_FIELD)
+        && let (_, Value::Str(name)) = &record.fields[i]
+        && let Ok(level) = name.parse()
+    {
+        record.level = level;
+        record.fields.remove(i);
+    }
+}
+
+#[cfg(feature = "log")]
+mod log_bridge {
+    use log::kv::{self, VisitSource};
+
+    use super::*;
+    use crate::{Result, SysxError, io::log::flush};
+
+    /// `log::Log` implementation that forwards to the global sysx `Logger`.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct LogBridge;
+
+    /// Maps a `log` level to the matching `LogLevel`.
+    pub fn from_log_level(level: log::Level) -> LogLevel {
+        match level {
+            log::Level::Error => LogLevel::Error,
+            log::Level::Warn => LogLevel::Warning,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Trace => LogLevel::Trace,
+        }
+    }
+
+    struct FieldVisitor<'a>(&'a mut Vec<(String, Value)>);
+
+    impl<'kvs> VisitSource<'kvs> for FieldVisitor<'_> {
+        fn visit_pair(
+            &mut self,
+            key: kv::Key<'kvs>,
+            value: kv::Value<'kvs>,
+        ) -> std::result::Result<(), kv::Error> {
+            let value = if let Some(v) = value.to_bool() {
+                Value::Bool(v)
+            } else if let Some(v) = value.to_i64() {
+                Value::Int(v)
+            } else if let Some(v) = value.to_u64() {
+                Value::UInt(v)
+            } else if let Some(v) = value.to_f64() {
+                Value::Float(v)
+            } else {
+                Value::Str(value.to_string())
+            };
+            self.0.push((key.as_str().to_string(), value));
+            Ok(())
+        }
+    }
+
+    impl log::Log for LogBridge {
+        fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+            // `sysx_level` may raise the level later, so only the mapped level is checked.
+            enabled(from_log_level(metadata.level()), metadata.target())
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            let mut fields = Vec::new();
+            let _ = record.key_values().visit(&mut FieldVisitor(&mut fields));
+
+            let mut sysx_record = Record::new(
+                from_log_level(record.level()),
+                record.args().to_string(),
+                None,
+                record.module_path().unwrap_or(record.target()),
+                record.file().unwrap_or("<unknown>"),
+                record.line().unwrap_or(0),
+            )
+            .with_fields(fields);
+            apply_level_override(&mut sysx_record);
+            dispatch(&sysx_record);
+        }
+
+        fn flush(&self) {
+            flush();
+        }
+    }
+
+    /// Installs `LogBridge` as the `log` crate's logger.
+    ///
+    /// All levels are forwarded; filtering is left to the sysx `Logger`. Fails if another
+    /// `log` logger is already installed.
+    pub fn install_log_bridge() -> Result<()> {
+        log::set_logger(&LogBridge).map_err(|e| {
+            SysxError::AnyhowError(anyhow::anyhow!("Failed to install log bridge: {e}"))
+        })?;
+        log::set_max_level(log::LevelFilter::Trace);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "log")]
+pub use log_bridge::*;
+
+#[cfg(feature = "tracing")]
+mod tracing_bridge {
+    use std::fmt;
+
+    use tracing::{
+        Event,
+        Subscriber,
+        field::{Field, Visit},
+        subscriber::Interest,
+    };
+    use tracing_subscriber::{
+        Layer,
+        layer::{Context, SubscriberExt},
+    };
+
+    use super::*;
+    use crate::{Result, SysxError};
+
+    /// `tracing_subscriber` layer that forwards events to the global sysx `Logger`.
+    ///
+    /// The `message` field becomes the record message; other fields keep their types.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct SysxLayer;
+
+    /// Maps a `tracing` level to the matching `LogLevel`.
+    pub fn from_tracing_level(level: &tracing::Level) -> LogLevel {
+        match *level {
+            tracing::Level::ERROR => LogLevel::Error,
+            tracing::Level::WARN => LogLevel::Warning,
+            tracing::Level::INFO => LogLevel::Info,
+            tracing::Level::DEBUG => LogLevel::Debug,
+            tracing::Level::TRACE => LogLevel::Trace,
+        }
+    }
+
+    #[derive(Default)]
+    struct EventVisitor {
+        message: String,
+        fields:  Vec<(String, Value)>,
+    }
+
+    impl EventVisitor {
+        fn push(&mut self, field: &Field, value: Value) {
+            if field.name() == "message" {
+                self.message = value.to_string();
+            } else {
+                self.fields.push((field.name().to_string(), value));
+            }
+        }
+    }
+
+    impl Visit for EventVisitor {
+        fn record_bool(&mut self, field: &Field, value: bool) {
+            self.push(field, Value::Bool(value));
+        }
+
+        fn record_i64(&mut self, field: &Field, value: i64) {
+            self.push(field, Value::Int(value));
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            self.push(field, Value::UInt(value));
+        }
+
+        fn record_f64(&mut self, field: &Field, value: f64) {
+            self.push(field, Value::Float(value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "message" {
+                self.message = value.to_string();
+            } else {
+                self.push(field, Value::Str(value.to_string()));
+            }
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                self.message = format!("{value:?}");
+            } else {
+                self.push(field, Value::Str(format!("{value:?}")));
+            }
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for SysxLayer {
+        fn register_callsite(&self, _metadata: &'static tracing::Metadata<'static>) -> Interest {
+            // The global logger can change at any time, so never let `tracing` cache a
+            // callsite as always or never enabled; `enabled` decides on every event.
+            Interest::sometimes()
+        }
+
+        fn enabled(&self, metadata: &tracing::Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+            // `sysx_level` may raise the level later, so only the mapped level is checked.
+            enabled(from_tracing_level(metadata.level()), metadata.target())
+        }
+
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let metadata = event.metadata();
+            let mut visitor = EventVisitor::default();
+            event.record(&mut visitor);
+
+            let mut record = Record::new(
+                from_tracing_level(metadata.level()),
+                visitor.message,
+                None,
+                metadata.module_path().unwrap_or(metadata.target()),
+                metadata.file().unwrap_or("<unknown>"),
+                metadata.line().unwrap_or(0),
+            )
+            .with_fields(visitor.fields);
+            apply_level_override(&mut record);
+            dispatch(&record);
+        }
+    }
+
+    /// Installs a global `tracing` subscriber that only contains `SysxLayer`.
+    ///
+    /// To combine it with other layers, add `SysxLayer` to your own registry instead.
+    pub fn install_tracing_subscriber() -> Result<()> {
+        let subscriber = tracing_subscriber::registry().with(SysxLayer);
+        tracing::subscriber::set_global_default(subscriber).map_err(|e| {
+            SysxError::AnyhowError(anyhow::anyhow!("Failed to install tracing subscriber: {e}"))
+        })
+    }
+}
+
+#[cfg(feature = "tracing")]
+pub use tracing_bridge::*;
@@ -0,0 +1,170 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{LogLevel, Record};
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Rc<RefCell<Vec<Record>>>>> = const { RefCell::new(None) };
+}
+
+/// Returns `true` if a `LogCapture` is active on the current thread.
+pub fn is_capturing() -> bool {
+    CAPTURE.with(|c| c.borrow().is_some())
+}
+
+/// Adds `record` to the current thread's capture, if any.
+pub(crate) fn record_captured(record: &Record) {
+    CAPTURE.with(|c| {
+        if let Some(records) = &*c.borrow() {
+            records.borrow_mut().push(record.clone());
+        }
+    });
+}
+
+/// Records every record logged through the global logger on the current thread.
+///
+/// Captured records bypass the logger's level filters, so tests see `Debug` and `Trace`
+/// output regardless of `SYSX_LOG`; records from other threads are not captured. Records
+/// still reach the logger's sinks as usual. Dropping the capture restores the previous one.
+///
+/// ```no_run
+/// use sysx::{assert_logged, io::log::capture_logs, log};
+///
+/// let logs = capture_logs();
+/// log!(WARNING, "request timeout after {}s", 5);
+/// assert_logged!(WARNING, contains "timeout");
+/// assert_eq!(logs.records().len(), 1);
+/// ```
+#[must_use = "capturing stops as soon as the guard is dropped"]
+pub struct LogCapture {
+    records:  Rc<RefCell<Vec<Record>>>,
+    previous: Option<Rc<RefCell<Vec<Record>>>>,
+}
+
+/// Starts capturing records logged on the current thread.
+pub fn capture_logs() -> LogCapture {
+    let records = Rc::new(RefCell::new(Vec::new()));
+    let previous = CAPTURE.with(|c| c.replace(Some(Rc::clone(&records))));
+    LogCapture { records, previous }
+}
+
+impl LogCapture {
+    /// Returns a copy of the records captured so far.
+    pub fn records(&self) -> Vec<Record> {
+        self.records.borrow().clone()
+    }
+
+    /// Returns the messages captured so far.
+    pub fn messages(&self) -> Vec<String> {
+        self.records
+            .borrow()
+            .iter()
+            .map(|r| r.message.clone())
+            .collect()
+    }
+
+    /// Discards the records captured so far.
+    pub fn clear(&self) {
+        self.records.borrow_mut().clear();
+    }
+
+    /// Returns `true` if a record at `level` matches `predicate`.
+    pub fn any<F: Fn(&Record) -> bool>(&self, level: LogLevel, predicate: F) -> bool {
+        self.records
+            .borrow()
+            .iter()
+            .any(|r| r.level == level && predicate(r))
+    }
+}
+
+impl Drop for LogCapture {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CAPTURE.with(|c| *c.borrow_mut() = previous);
+    }
+}
+
+/// Checks the current thread's capture for a record at `level` matching `predicate`.
+///
+/// Used by `assert_logged!` and `assert_not_logged!`; panics with the captured records
+/// when the outcome differs from `expected`.
+#[doc(hidden)]
+pub fn check_captured<F>(level: LogLevel, expected: bool, description: &str, predicate: F)
+where
+    F: Fn(&Record) -> bool,
+{
+    let records = CAPTURE.with(|c| c.borrow().as_ref().map(|r| r.borrow().clone()));
+    let Some(records) = records else {
+        panic!("No log capture is active on this thread; call `capture_logs()` first");
+    };
+
+    let found = records.iter().any(|r| r.level == level && predicate(r));
+    if found != expected {
+        let captured: Vec<String> = records
+            .iter()
+            .map(|r| format!("  [{}] {}", r.level, r.message))
+            .collect();
+        panic!(
+            "Expected {}a {level} record {description}; captured:\n{}",
+            if expected { "" } else { "no " },
+            if captured.is_empty() {
+                "  (nothing)".to_string()
+            } else {
+                captured.join("\n")
+            }
+        );
+    }
+}
+
+/// Asserts that a record was captured by `capture_logs` on this thread.
+///
+/// `assert_logged!(WARNING, contains "timeout")` matches a substring of the message,
+/// `assert_logged!(INFO, "started")` the whole message, and
+/// `assert_logged!(INFO, field "retries" == 3)` a structured field.
+#[macro_export]
+macro_rules! assert_logged {
+    ($level:ident, $($pattern:tt)+) => {
+        $crate::__log_capture_check!(true, $level, $($pattern)+)
+    };
+}
+pub use assert_logged;
+
+/// Asserts that no matching record was captured. Takes the same patterns as `assert_logged!`.
+#[macro_export]
+macro_rules! assert_not_logged {
+    ($level:ident, $($pattern:tt)+) => {
+        $crate::__log_capture_check!(false, $level, $($pattern)+)
+    };
+}
+pub use assert_not_logged;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_capture_check {
+    ($expected:expr, $level:ident, contains $text:expr) => {{
+        let text: &str = &$text;
+        $crate::io::log::check_captured(
+            $crate::log_level!($level),
+            $expected,
+            &format!("containing {text:?}"),
+            |record| record.message.contains(text),
+        )
+    }};
+    ($expected:expr, $level:ident, field $key:literal == $value:expr) => {{
+        let value = $crate::io::log::ToValue::to_value(&$value);
+        $crate::io::log::check_captured(
+            $crate::log_level!($level),
+            $expected,
+            &format!("with field {}={value}", $key),
+            |record| record.field($key) == Some(&value),
+        )
+    }};
+    ($expected:expr, $level:ident, $message:expr) => {{
+        let message: &str = &$message;
+        $crate::io::log::check_captured(
+            $crate::log_level!($level),
+            $expected,
+            &format!("with message {message:?}"),
+            |record| record.message == message,
+        )
+    }};
+}
@@ -0,0 +1,154 @@
+use std::fmt::Write;
+
+use super::{LogTheme, Record, Value, theme, theme::dim};
+
+/// Output format used by the built-in sinks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Human-readable text, coloured when writing to a terminal.
+    #[default]
+    Pretty,
+    /// One JSON object per line.
+    Json,
+    /// `key=value` pairs, one record per line.
+    Logfmt,
+}
+
+impl Format {
+    /// Renders `record` as a single entry without a trailing newline.
+    ///
+    /// `color` only affects `Format::Pretty`; machine-readable formats are never coloured.
+    pub fn render(&self, record: &Record, color: bool) -> String {
+        match self {
+            Format::Pretty => format_pretty(record, color),
+            Format::Json => format_json(record),
+            Format::Logfmt => format_logfmt(record),
+        }
+    }
+}
+
+/// Renders a record with the current theme: timestamp, `[LEVEL] message`, fields, then
+/// context on its own line.
+pub fn format_pretty(record: &Record, color: bool) -> String {
+    format_pretty_with(record, &theme(), color)
+}
+
+/// Renders a record like `format_pretty`, using `theme` instead of the current one.
+pub fn format_pretty_with(record: &Record, theme: &LogTheme, color: bool) -> String {
+    let style = theme.level_style(record.level);
+    let timestamp = record
+        .timestamp
+        .format(theme.get_timestamp_format())
+        .to_string();
+    let indent = "  ".repeat(record.depth);
+    let message = format!("{} {indent}{}", style.prefix, record.message);
+    let fields = record
+        .fields
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(theme.get_field_separator());
+
+    let mut line = if color {
+        format!("{} {}", dim(&timestamp), style.paint(&message))
+    } else {
+        format!("{timestamp} {message}")
+    };
+    if !fields.is_empty() {
+        let fields = if color { dim(&fields) } else { fields };
+        line.push_str(&format!(" {fields}"));
+    }
+
+    if let Some(context) = &record.context {
+        let context = if color { dim(context) } else { context.clone() };
+        line.push_str(&format!("\n  ↳ {context}"));
+    }
+    line
+}
+
+fn json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn json_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+        Value::Int(v) => out.push_str(&v.to_string()),
+        Value::UInt(v) => out.push_str(&v.to_string()),
+        Value::Float(v) if v.is_finite() => out.push_str(&v.to_string()),
+        Value::Float(_) => out.push_str("null"),
+        Value::Str(v) => json_string(out, v),
+    }
+}
+
+/// Renders a record as a JSON Lines object with `timestamp`, `level`, `message`,
+/// `context`, `module`, `file` (as `path:line`) and `fields` keys.
+pub fn format_json(record: &Record) -> String {
+    let mut out = String::from("{\"timestamp\":");
+    json_string(&mut out, &record.timestamp.to_rfc3339());
+    out.push_str(",\"level\":");
+    json_string(&mut out, record.level.name());
+    out.push_str(",\"message\":");
+    json_string(&mut out, &record.message);
+    out.push_str(",\"context\":");
+    match &record.context {
+        Some(context) => json_string(&mut out, context),
+        None => out.push_str("null"),
+    }
+    out.push_str(",\"module\":");
+    json_string(&mut out, &record.module);
+    out.push_str(",\"file\":");
+    json_string(&mut out, &format!("{}:{}", record.file, record.line));
+    out.push_str(",\"fields\":{");
+    for (i, (key, value)) in record.fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        json_string(&mut out, key);
+        out.push(':');
+        json_value(&mut out, value);
+    }
+    out.push_str("}}");
+    out
+}
+
+/// Renders a record as logfmt: `ts`, `level`, `msg`, `ctx`, `module`, `file`, then fields.
+pub fn format_logfmt(record: &Record) -> String {
+    let mut pairs = vec![
+        ("ts".to_string(), Value::Str(record.timestamp.to_rfc3339())),
+        (
+            "level".to_string(),
+            Value::Str(record.level.name().to_lowercase()),
+        ),
+        ("msg".to_string(), Value::Str(record.message.clone())),
+    ];
+    if let Some(context) = &record.context {
+        pairs.push(("ctx".to_string(), Value::Str(context.clone())));
+    }
+    pairs.push(("module".to_string(), Value::Str(record.module.clone())));
+    pairs.push((
+        "file".to_string(),
+        Value::Str(format!("{}:{}", record.file, record.line)),
+    ));
+
+    pairs
+        .iter()
+        .chain(&record.fields)
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
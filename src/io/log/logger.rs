@@ -0,0 +1,188 @@
+use std::{
+    env,
+    sync::{Arc, RwLock},
+};
+
+use once_cell::sync::Lazy;
+
+use super::{LogLevel, Record, Sink, StdoutSink, is_capturing, record_captured};
+use crate::{Result, SysxError};
+
+/// Environment variable read by the default global logger, e.g. `warn,mycrate::net=trace`.
+pub const LOG_ENV: &str = "SYSX_LOG";
+
+static LOGGER: Lazy<RwLock<Arc<Logger>>> = Lazy::new(|| {
+    let logger = Logger::new().sink(StdoutSink::new());
+    let logger = match logger.clone().env_filter() {
+        Ok(filtered) => filtered,
+        Err(e) => {
+            eprintln!("Ignoring {LOG_ENV}: {e}");
+            logger
+        }
+    };
+    RwLock::new(Arc::new(logger))
+});
+
+/// Filters records by level and module, then hands them to its sinks.
+///
+/// ```no_run
+/// use sysx::io::log::{FileSink, LogLevel, Logger, StderrSink, set_logger};
+///
+/// set_logger(
+///     Logger::new()
+///         .level(LogLevel::Info)
+///         .module_level("mycrate::net", LogLevel::Trace)
+///         .sink(StderrSink::new())
+///         .sink(FileSink::open("app.log").unwrap()),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct Logger {
+    level:   LogLevel,
+    modules: Vec<(String, LogLevel)>,
+    sinks:   Vec<Arc<dyn Sink>>,
+}
+
+impl Logger {
+    /// Creates a logger that accepts every level and has no sinks yet.
+    pub fn new() -> Self {
+        Self {
+            level:   LogLevel::Trace,
+            modules: Vec::new(),
+            sinks:   Vec::new(),
+        }
+    }
+
+    /// Sets the minimum level for modules without an override.
+    pub fn level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets the minimum level for `module` and its submodules.
+    ///
+    /// When several overrides match, the longest module path wins.
+    pub fn module_level(mut self, module: &str, level: LogLevel) -> Self {
+        self.modules.retain(|(m, _)| m != module);
+        self.modules.push((module.to_string(), level));
+        self
+    }
+
+    /// Applies a filter of comma-separated directives: `level` sets the default minimum,
+    /// `module=level` overrides it for a module, and a bare `module` enables all its levels.
+    ///
+    /// For example `warn,mycrate::net=trace,mycrate::db=error`.
+    pub fn filter(mut self, spec: &str) -> Result<Self> {
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let module = module.trim();
+                    if module.is_empty() {
+                        return Err(SysxError::InvalidSyntax(format!(
+                            "Missing module name in log filter directive '{directive}'"
+                        )));
+                    }
+                    self = self.module_level(module, level.parse()?);
+                }
+                None => match directive.parse() {
+                    Ok(level) => self.level = level,
+                    Err(_) => self = self.module_level(directive, LogLevel::Trace),
+                },
+            }
+        }
+        Ok(self)
+    }
+
+    /// Applies the filter in `SYSX_LOG`, if set. See `Logger::filter` for the syntax.
+    pub fn env_filter(self) -> Result<Self> {
+        match env::var(LOG_ENV) {
+            Ok(spec) => self.filter(&spec),
+            Err(_) => Ok(self),
+        }
+    }
+
+    /// Adds a sink. Records go to every sink in the order they were added.
+    pub fn sink<S: Sink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Returns the minimum level that applies to `module`.
+    pub fn level_for(&self, module: &str) -> LogLevel {
+        self.modules
+            .iter()
+            .filter(|(prefix, _)| {
+                module == prefix
+                    || module
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.level, |(_, level)| *level)
+    }
+
+    /// Returns `true` if a record at `level` from `module` would be written.
+    pub fn enabled(&self, level: LogLevel, module: &str) -> bool {
+        level >= self.level_for(module)
+    }
+
+    /// Writes `record` to every sink if it passes the filters.
+    ///
+    /// Sink errors are ignored so logging never fails the caller. `Fatal` records
+    /// flush all sinks.
+    pub fn log(&self, record: &Record) {
+        if !self.enabled(record.level, &record.module) {
+            return;
+        }
+        for sink in &self.sinks {
+            let _ = sink.write(record);
+        }
+        if record.level == LogLevel::Fatal {
+            self.flush();
+        }
+    }
+
+    /// Flushes every sink.
+    pub fn flush(&self) {
+        for sink in &self.sinks {
+            let _ = sink.flush();
+        }
+    }
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Replaces the global logger used by `log!`.
+pub fn set_logger(logger: Logger) {
+    let previous = std::mem::replace(
+        &mut *LOGGER.write().unwrap_or_else(|e| e.into_inner()),
+        Arc::new(logger),
+    );
+    previous.flush();
+}
+
+/// Returns the global logger. By default it writes to stdout, filtered by `SYSX_LOG`.
+pub fn logger() -> Arc<Logger> {
+    LOGGER.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Returns `true` if the global logger would write `level` from `module`, or if a
+/// `LogCapture` is active on this thread.
+pub fn enabled(level: LogLevel, module: &str) -> bool {
+    is_capturing() || logger().enabled(level, module)
+}
+
+/// Sends `record` to the current thread's `LogCapture`, if any, and the global logger.
+pub fn dispatch(record: &Record) {
+    record_captured(record);
+    logger().log(record);
+}
+
+/// Flushes every sink of the global logger.
+pub fn flush() {
+    logger().flush();
+}
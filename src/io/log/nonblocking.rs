@@ -0,0 +1,244 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread::{self, JoinHandle},
+};
+
+use anyhow::Context;
+
+use super::{LogLevel, Record, Sink, Value};
+use crate::{Result, SysxError};
+
+/// Default queue length of an `AsyncSink`.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+/// What `AsyncSink` does when its queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Wait for the writer thread to make room.
+    #[default]
+    Block,
+    /// Discard the record being logged.
+    DropNewest,
+    /// Discard the oldest queued record to make room.
+    DropOldest,
+}
+
+struct State {
+    queue:    VecDeque<Record>,
+    /// Records discarded since the last "dropped" notice.
+    dropped:  u64,
+    /// The writer thread is writing a record outside the lock.
+    busy:     bool,
+    shutdown: bool,
+}
+
+struct Shared {
+    state:     Mutex<State>,
+    /// Signalled when records are queued or shutdown starts.
+    available: Condvar,
+    /// Signalled when the writer takes a record or becomes idle.
+    drained:   Condvar,
+    sink:      Arc<dyn Sink>,
+    capacity:  usize,
+    overflow:  Overflow,
+    worker:    Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Writes records until shutdown, reporting drops as a `Warning` record.
+    fn run(&self) {
+        let mut state = self.lock();
+        loop {
+            while state.queue.is_empty() && state.dropped == 0 && !state.shutdown {
+                state = self
+                    .available
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner());
+            }
+
+            let record = if state.dropped > 0 {
+                Some(dropped_notice(std::mem::take(&mut state.dropped)))
+            } else {
+                state.queue.pop_front()
+            };
+            let Some(record) = record else {
+                // Queue drained after shutdown.
+                self.drained.notify_all();
+                return;
+            };
+
+            state.busy = true;
+            drop(state);
+            self.drained.notify_all();
+            let _ = self.sink.write(&record);
+            state = self.lock();
+            state.busy = false;
+            self.drained.notify_all();
+        }
+    }
+}
+
+fn dropped_notice(count: u64) -> Record {
+    Record::new(
+        LogLevel::Warning,
+        format!("Log queue overflowed, dropped {count} records"),
+        None,
+        module_path!(),
+        file!(),
+        line!(),
+    )
+    .with_fields(vec![("dropped".to_string(), Value::UInt(count))])
+}
+
+/// Sink that hands records to a background thread, so logging does not wait on I/O.
+///
+/// Clones share the same queue and thread; keep one to call `flush` or `shutdown` after
+/// passing another to `Logger::sink`. `Fatal` records are never dropped and are written
+/// before `log!` returns.
+///
+/// ```no_run
+/// use sysx::io::log::{AsyncSink, Logger, Overflow, StdoutSink, set_logger};
+///
+/// let sink = AsyncSink::new(StdoutSink::new(), 4096, Overflow::DropOldest).unwrap();
+/// let _shutdown = sink.shutdown_guard();
+/// set_logger(Logger::new().sink(sink.clone()));
+/// ```
+#[derive(Clone)]
+pub struct AsyncSink {
+    shared: Arc<Shared>,
+}
+
+impl AsyncSink {
+    /// Starts the writer thread for `sink` with a queue of `capacity` records.
+    pub fn new<S: Sink + 'static>(sink: S, capacity: usize, overflow: Overflow) -> Result<Self> {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                queue:    VecDeque::with_capacity(capacity.min(DEFAULT_QUEUE_CAPACITY)),
+                dropped:  0,
+                busy:     false,
+                shutdown: false,
+            }),
+            available: Condvar::new(),
+            drained: Condvar::new(),
+            sink: Arc::new(sink),
+            capacity: capacity.max(1),
+            overflow,
+            worker: Mutex::new(None),
+        });
+
+        let worker = Arc::clone(&shared);
+        let handle = thread::Builder::new()
+            .name("sysx-log".into())
+            .spawn(move || worker.run())
+            .context("Failed to start log writer thread")
+            .map_err(SysxError::AnyhowError)?;
+        *shared.worker.lock().unwrap_or_else(|e| e.into_inner()) = Some(handle);
+
+        Ok(Self { shared })
+    }
+
+    /// Returns the number of records waiting to be written.
+    pub fn pending(&self) -> usize {
+        self.shared.lock().queue.len()
+    }
+
+    fn wait_idle(&self) {
+        let mut state = self.shared.lock();
+        while !state.queue.is_empty() || state.dropped > 0 || state.busy {
+            state = self
+                .shared
+                .drained
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Drains the queue, writes the remaining records and stops the writer thread.
+    ///
+    /// Later records are written synchronously on the caller's thread.
+    pub fn shutdown(&self) {
+        self.shared.lock().shutdown = true;
+        self.shared.available.notify_all();
+
+        let handle = self
+            .shared
+            .worker
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(handle) = handle {
+            let _ = handle.join();
+        }
+        let _ = self.shared.sink.flush();
+    }
+
+    /// Returns a guard that calls `shutdown` when dropped, e.g. at the end of `main`.
+    pub fn shutdown_guard(&self) -> ShutdownGuard {
+        ShutdownGuard(self.clone())
+    }
+}
+
+impl Sink for AsyncSink {
+    fn write(&self, record: &Record) -> Result<()> {
+        let fatal = record.level == LogLevel::Fatal;
+        let mut state = self.shared.lock();
+        if state.shutdown {
+            drop(state);
+            return self.shared.sink.write(record);
+        }
+
+        while state.queue.len() >= self.shared.capacity {
+            match (fatal, self.shared.overflow) {
+                (true, _) | (_, Overflow::Block) => {}
+                (_, Overflow::DropNewest) => {
+                    state.dropped += 1;
+                    return Ok(());
+                }
+                (_, Overflow::DropOldest) => {
+                    state.queue.pop_front();
+                    state.dropped += 1;
+                    continue;
+                }
+            }
+            state = self
+                .shared
+                .drained
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+            if state.shutdown {
+                drop(state);
+                return self.shared.sink.write(record);
+            }
+        }
+
+        state.queue.push_back(record.clone());
+        drop(state);
+        self.shared.available.notify_one();
+
+        if fatal {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Waits until every queued record is written, then flushes the wrapped sink.
+    fn flush(&self) -> Result<()> {
+        self.wait_idle();
+        self.shared.sink.flush()
+    }
+}
+
+/// Shuts an `AsyncSink` down when dropped. See `AsyncSink::shutdown_guard`.
+#[must_use = "the sink shuts down as soon as the guard is dropped"]
+pub struct ShutdownGuard(AsyncSink);
+
+impl Drop for ShutdownGuard {
+    fn drop(&mut self) {
+        self.0.shutdown();
+    }
+}
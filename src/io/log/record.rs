@@ -0,0 +1,55 @@
+use chrono::{DateTime, Local};
+
+use super::{LogLevel, Value, span_depth};
+
+/// A single log event, as handed to every `Sink`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub level:     LogLevel,
+    pub message:   String,
+    pub context:   Option<String>,
+    /// Module path of the call site, e.g. `mycrate::net`.
+    pub module:    String,
+    pub file:      String,
+    pub line:      u32,
+    pub timestamp: DateTime<Local>,
+    /// Structured `key = value` fields, in call-site order.
+    pub fields:    Vec<(String, Value)>,
+    /// Number of spans open on the logging thread; pretty output is indented by it.
+    pub depth:     usize,
+}
+
+impl Record {
+    /// Creates a record stamped with the current local time and span depth.
+    pub fn new(
+        level: LogLevel,
+        message: impl Into<String>,
+        context: Option<String>,
+        module: impl Into<String>,
+        file: impl Into<String>,
+        line: u32,
+    ) -> Self {
+        Self {
+            level,
+            message: message.into(),
+            context,
+            module: module.into(),
+            file: file.into(),
+            line,
+            timestamp: Local::now(),
+            fields: Vec::new(),
+            depth: span_depth(),
+        }
+    }
+
+    /// Attaches structured fields.
+    pub fn with_fields(mut self, fields: Vec<(String, Value)>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Returns the value of the first field named `key`.
+    pub fn field(&self, key: &str) -> Option<&Value> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
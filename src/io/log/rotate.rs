@@ -0,0 +1,244 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Local};
+
+use super::{Format, LogLevel, Record, Sink};
+use crate::{Result, SysxError};
+
+/// Time-based rotation period for `RotatingFileSink`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Hourly,
+    Daily,
+}
+
+impl Period {
+    fn key(&self, time: &DateTime<Local>) -> String {
+        match self {
+            Period::Hourly => time.format("%Y-%m-%d %H").to_string(),
+            Period::Daily => time.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+struct Active {
+    writer:     BufWriter<File>,
+    size:       u64,
+    /// Time of the last write, used to detect a new rotation period.
+    last_write: DateTime<Local>,
+}
+
+/// Appends records to a file, moving it aside when it grows too large or a new
+/// hour/day begins.
+///
+/// Archives are named `<stem>.<timestamp>.<ext>` next to the log file, e.g.
+/// `app.2024-05-01T12-30-00.250.log`. Writes are buffered; the buffer is flushed on
+/// `Fatal` records, on `Sink::flush` and when the sink is dropped.
+///
+/// ```no_run
+/// use sysx::io::log::{Logger, Period, RotatingFileSink, set_logger};
+///
+/// let sink = RotatingFileSink::open("logs/app.log")
+///     .unwrap()
+///     .max_size(10 * 1024 * 1024)
+///     .period(Period::Daily)
+///     .keep(7);
+/// set_logger(Logger::new().sink(sink));
+/// ```
+pub struct RotatingFileSink {
+    path:     PathBuf,
+    format:   Format,
+    max_size: Option<u64>,
+    period:   Option<Period>,
+    keep:     Option<usize>,
+    active:   Mutex<Active>,
+}
+
+fn open_active(path: &Path) -> Result<Active> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open log file '{}'", path.display()))
+        .map_err(SysxError::AnyhowError)?;
+    let metadata = file.metadata()?;
+    let last_write = match metadata.modified() {
+        Ok(modified) if metadata.len() > 0 => modified.into(),
+        _ => Local::now(),
+    };
+
+    Ok(Active {
+        writer: BufWriter::new(file),
+        size: metadata.len(),
+        last_write,
+    })
+}
+
+impl RotatingFileSink {
+    /// Opens `path` for appending, creating it and its parent directory if needed.
+    ///
+    /// Without further configuration the file is never rotated.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create '{}'", parent.display()))
+                .map_err(SysxError::AnyhowError)?;
+        }
+
+        Ok(Self {
+            active: Mutex::new(open_active(&path)?),
+            path,
+            format: Format::Pretty,
+            max_size: None,
+            period: None,
+            keep: None,
+        })
+    }
+
+    /// Sets the output format.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Rotates before a write would make the file larger than `bytes`.
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Rotates when the first record of a new hour or day is written.
+    pub fn period(mut self, period: Period) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Keeps at most `count` archives, deleting the oldest ones.
+    pub fn keep(mut self, count: usize) -> Self {
+        self.keep = Some(count);
+        self
+    }
+
+    /// Returns the path of the active log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns existing archives of this log file, oldest first.
+    pub fn archives(&self) -> Result<Vec<PathBuf>> {
+        let (stem, ext) = self.name_parts();
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let prefix = format!("{stem}.");
+
+        let mut archives: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix(&prefix))
+                    .and_then(|rest| rest.strip_suffix(&ext))
+                    .is_some_and(|stamp| stamp.starts_with(|c: char| c.is_ascii_digit()))
+            })
+            .collect();
+        archives.sort();
+        Ok(archives)
+    }
+
+    fn name_parts(&self) -> (String, String) {
+        let stem = self
+            .path
+            .file_stem()
+            .map_or_else(|| "log".into(), |s| s.to_string_lossy().into_owned());
+        let ext = self
+            .path
+            .extension()
+            .map_or_else(String::new, |e| format!(".{}", e.to_string_lossy()));
+        (stem, ext)
+    }
+
+    fn archive_path(&self) -> PathBuf {
+        let (stem, ext) = self.name_parts();
+        let stamp = Local::now().format("%Y-%m-%dT%H-%M-%S%.3f");
+        let mut archive = self.path.with_file_name(format!("{stem}.{stamp}{ext}"));
+        // `_` sorts after `.`, so same-millisecond archives stay in rotation order.
+        let mut n = 1;
+        while archive.exists() {
+            archive = self.path.with_file_name(format!("{stem}.{stamp}_{n}{ext}"));
+            n += 1;
+        }
+        archive
+    }
+
+    fn should_rotate(&self, active: &Active, now: &DateTime<Local>, incoming: u64) -> bool {
+        if active.size == 0 {
+            return false;
+        }
+        let too_large = self
+            .max_size
+            .is_some_and(|max| active.size + incoming > max);
+        let new_period = self
+            .period
+            .is_some_and(|period| period.key(&active.last_write) != period.key(now));
+        too_large || new_period
+    }
+
+    fn rotate(&self, active: &mut Active) -> Result<()> {
+        active.writer.flush()?;
+        let archive = self.archive_path();
+        fs::rename(&self.path, &archive)
+            .with_context(|| format!("Failed to rotate log file to '{}'", archive.display()))
+            .map_err(SysxError::AnyhowError)?;
+        *active = open_active(&self.path)?;
+
+        if let Some(keep) = self.keep {
+            let archives = self.archives()?;
+            let excess = archives.len().saturating_sub(keep);
+            for old in &archives[..excess] {
+                fs::remove_file(old)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Sink for RotatingFileSink {
+    fn write(&self, record: &Record) -> Result<()> {
+        let line = format!("{}\n", self.format.render(record, false));
+        let now = Local::now();
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+
+        if self.should_rotate(&active, &now, line.len() as u64) {
+            self.rotate(&mut active)?;
+        }
+        active.writer.write_all(line.as_bytes())?;
+        active.size += line.len() as u64;
+        active.last_write = now;
+
+        if record.level == LogLevel::Fatal {
+            active.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        active.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for RotatingFileSink {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
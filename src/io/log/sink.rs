@@ -0,0 +1,204 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, IsTerminal, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context;
+
+use super::{Format, Record, should_color};
+use crate::{Result, SysxError};
+
+/// Destination for log records.
+///
+/// Sinks are shared between threads, so `write` takes `&self`; use interior mutability
+/// for any state.
+pub trait Sink: Send + Sync {
+    /// Writes one record.
+    fn write(&self, record: &Record) -> Result<()>;
+
+    /// Flushes buffered output. Called after every `Fatal` record.
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes records to stdout.
+///
+/// Pretty output is coloured when stdout is a terminal (see `should_color`), unless
+/// overridden with `color`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink {
+    format: Format,
+    color:  Option<bool>,
+}
+
+impl StdoutSink {
+    /// Creates a sink using `Format::Pretty`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the output format.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Forces colour on or off instead of detecting a terminal.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl Sink for StdoutSink {
+    fn write(&self, record: &Record) -> Result<()> {
+        let stdout = io::stdout();
+        let color = self
+            .color
+            .unwrap_or_else(|| should_color(stdout.is_terminal()));
+        writeln!(stdout.lock(), "{}", self.format.render(record, color))?;
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        io::stdout().flush()?;
+        Ok(())
+    }
+}
+
+/// Writes records to stderr.
+///
+/// Pretty output is coloured when stderr is a terminal (see `should_color`), unless
+/// overridden with `color`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StderrSink {
+    format: Format,
+    color:  Option<bool>,
+}
+
+impl StderrSink {
+    /// Creates a sink using `Format::Pretty`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the output format.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Forces colour on or off instead of detecting a terminal.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl Sink for StderrSink {
+    fn write(&self, record: &Record) -> Result<()> {
+        let stderr = io::stderr();
+        let color = self
+            .color
+            .unwrap_or_else(|| should_color(stderr.is_terminal()));
+        writeln!(stderr.lock(), "{}", self.format.render(record, color))?;
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        io::stderr().flush()?;
+        Ok(())
+    }
+}
+
+/// Appends uncoloured records to a file.
+#[derive(Debug)]
+pub struct FileSink {
+    file:   Mutex<File>,
+    format: Format,
+}
+
+impl FileSink {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file '{}'", path.display()))
+            .map_err(SysxError::AnyhowError)?;
+        Ok(Self {
+            file:   Mutex::new(file),
+            format: Format::Pretty,
+        })
+    }
+
+    /// Sets the output format.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+impl Sink for FileSink {
+    fn write(&self, record: &Record) -> Result<()> {
+        let line = format!("{}\n", self.format.render(record, false));
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Keeps records in memory. Clones share the same buffer.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    records: Arc<Mutex<Vec<Record>>>,
+}
+
+impl MemorySink {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the records written so far.
+    pub fn records(&self) -> Vec<Record> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Returns the messages written so far.
+    pub fn messages(&self) -> Vec<String> {
+        self.records().into_iter().map(|r| r.message).collect()
+    }
+
+    /// Discards all records.
+    pub fn clear(&self) {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl Sink for MemorySink {
+    fn write(&self, record: &Record) -> Result<()> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(record.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use std::{cell::Cell, marker::PhantomData, time::Instant};
+
+use super::{LogLevel, Record, Value, dispatch, enabled};
+use crate::time::{Duration, humanize};
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Returns how many spans are open on the current thread.
+pub fn span_depth() -> usize {
+    DEPTH.with(Cell::get)
+}
+
+/// Guard that measures a block of code and logs when it ends.
+///
+/// Created by the `span!` macro (logs on entry and exit) or `timer!` (exit only). The exit
+/// record carries `elapsed` in a human unit such as `1.5s`. Records logged on the same
+/// thread while a span is open are indented in the pretty output.
+///
+/// ```no_run
+/// use sysx::{log, span, timer};
+///
+/// let _span = span!(INFO, "loading {}", "config");
+/// log!(DEBUG, "reading file"); // indented under the span
+/// let _timer = timer!(DEBUG, "parse");
+/// ```
+///
+/// A span stays on the thread that opened it, since it tracks that thread's nesting depth:
+///
+/// ```compile_fail
+/// let span = sysx::span!(INFO, "work");
+/// std::thread::spawn(move || drop(span));
+/// ```
+#[must_use = "the span ends as soon as the guard is dropped"]
+pub struct Span {
+    name:      String,
+    level:     LogLevel,
+    module:    &'static str,
+    file:      &'static str,
+    line:      u32,
+    start:     Instant,
+    /// Whether this span increased the nesting depth (`span!` but not `timer!`).
+    nested:    bool,
+    // `DEPTH` is per thread, so the span must be dropped where it was opened.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Span {
+    /// Opens a span, logging `"<name> started"` at `level`.
+    pub fn enter(
+        level: LogLevel,
+        name: String,
+        module: &'static str,
+        file: &'static str,
+        line: u32,
+    ) -> Self {
+        let mut span = Self::timer(level, name, module, file, line);
+        span.emit(format!("{} started", span.name), Vec::new());
+        DEPTH.with(|d| d.set(d.get() + 1));
+        span.nested = true;
+        span
+    }
+
+    /// Opens a span that only logs when it ends.
+    pub fn timer(
+        level: LogLevel,
+        name: String,
+        module: &'static str,
+        file: &'static str,
+        line: u32,
+    ) -> Self {
+        Self {
+            name,
+            level,
+            module,
+            file,
+            line,
+            start: Instant::now(),
+            nested: false,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the span name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the time since the span was opened.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Ends the span now and returns its duration.
+    pub fn finish(self) -> Duration {
+        self.elapsed()
+    }
+
+    fn emit(&self, message: String, fields: Vec<(String, Value)>) {
+        if enabled(self.level, self.module) {
+            dispatch(
+                &Record::new(self.level, message, None, self.module, self.file, self.line)
+                    .with_fields(fields),
+            );
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let elapsed = self.elapsed();
+        if self.nested {
+            DEPTH.with(|d| d.set(d.get().saturating_sub(1)));
+        }
+        let fields = vec![("elapsed".to_string(), Value::Str(humanize(elapsed)))];
+        self.emit(format!("{} finished", self.name), fields);
+    }
+}
+
+/// Opens a `Span` that logs on entry and exit at the given level and indents nested output.
+#[macro_export]
+macro_rules! span {
+    ($level:ident, $($name:tt)+) => {
+        $crate::io::log::Span::enter(
+            $crate::log_level!($level),
+            format!($($name)+),
+            module_path!(),
+            file!(),
+            line!(),
+        )
+    };
+}
+pub use span;
+
+/// Opens a `Span` that only logs its elapsed time when dropped.
+#[macro_export]
+macro_rules! timer {
+    ($level:ident, $($name:tt)+) => {
+        $crate::io::log::Span::timer(
+            $crate::log_level!($level),
+            format!($($name)+),
+            module_path!(),
+            file!(),
+            line!(),
+        )
+    };
+}
+pub use timer;
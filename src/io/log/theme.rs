@@ -0,0 +1,209 @@
+use std::{
+    env,
+    sync::{Arc, RwLock},
+};
+
+use colored::Color;
+use once_cell::sync::Lazy;
+
+use super::LogLevel;
+
+/// Timestamp format of the built-in themes.
+pub const DEFAULT_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+static THEME: Lazy<RwLock<Arc<LogTheme>>> =
+    Lazy::new(|| RwLock::new(Arc::new(LogTheme::default())));
+
+/// How one level is drawn in pretty output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelStyle {
+    pub fg:     Option<Color>,
+    pub bg:     Option<Color>,
+    pub bold:   bool,
+    /// Text placed before the message, e.g. `[INFO]` or an emoji.
+    pub prefix: String,
+}
+
+impl LevelStyle {
+    /// Bold text in `fg` with the usual `[LEVEL]` prefix.
+    pub fn new(level: LogLevel, fg: Option<Color>) -> Self {
+        Self {
+            fg,
+            bg: None,
+            bold: true,
+            prefix: format!("[{level}]"),
+        }
+    }
+
+    /// Sets the background colour.
+    pub fn on(mut self, bg: Color) -> Self {
+        self.bg = Some(bg);
+        self
+    }
+
+    /// Wraps `text` in this style's ANSI codes.
+    pub fn paint(&self, text: &str) -> String {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push("1".into());
+        }
+        if let Some(fg) = self.fg {
+            codes.push(fg.to_fg_str());
+        }
+        if let Some(bg) = self.bg {
+            codes.push(bg.to_bg_str());
+        }
+        paint(&codes.join(";"), text)
+    }
+}
+
+/// Wraps `text` in an SGR sequence. `colored` is bypassed on purpose: it disables colour
+/// whenever stdout is not a terminal, while each sink makes that decision itself.
+fn paint(codes: &str, text: &str) -> String {
+    if codes.is_empty() || text.is_empty() {
+        text.to_string()
+    } else {
+        format!("\x1b[{codes}m{text}\x1b[0m")
+    }
+}
+
+/// Dims `text`, as used for timestamps, fields and context.
+pub(crate) fn dim(text: &str) -> String {
+    paint("2", text)
+}
+
+/// Colours, prefixes and layout details for pretty log output.
+///
+/// ```no_run
+/// use sysx::io::log::{LogLevel, LogTheme, set_theme};
+///
+/// set_theme(
+///     LogTheme::high_contrast()
+///         .prefix(LogLevel::Success, "✅")
+///         .timestamp_format("%H:%M:%S")
+///         .field_separator(", "),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogTheme {
+    styles:           [LevelStyle; 8],
+    timestamp_format: String,
+    field_separator:  String,
+}
+
+const LEVELS: [LogLevel; 8] = [
+    LogLevel::Trace,
+    LogLevel::Debug,
+    LogLevel::Info,
+    LogLevel::Success,
+    LogLevel::Warning,
+    LogLevel::Error,
+    LogLevel::Bug,
+    LogLevel::Fatal,
+];
+
+impl LogTheme {
+    fn from_fn<F: Fn(LogLevel) -> LevelStyle>(style: F) -> Self {
+        Self {
+            styles:           LEVELS.map(style),
+            timestamp_format: DEFAULT_TIMESTAMP_FORMAT.into(),
+            field_separator:  " ".into(),
+        }
+    }
+
+    /// No colours; `Error` and worse are bold.
+    pub fn monochrome() -> Self {
+        Self::from_fn(|level| LevelStyle {
+            bold: level >= LogLevel::Error,
+            ..LevelStyle::new(level, None)
+        })
+    }
+
+    /// Bright colours, with solid backgrounds from `Success` up.
+    pub fn high_contrast() -> Self {
+        Self::from_fn(|level| {
+            let style = |fg| LevelStyle::new(level, Some(fg));
+            match level {
+                LogLevel::Trace => style(Color::BrightCyan),
+                LogLevel::Debug => style(Color::BrightMagenta),
+                LogLevel::Info => style(Color::BrightWhite).on(Color::Blue),
+                LogLevel::Success => style(Color::Black).on(Color::BrightGreen),
+                LogLevel::Warning => style(Color::Black).on(Color::BrightYellow),
+                LogLevel::Error => style(Color::BrightWhite).on(Color::Red),
+                LogLevel::Bug => style(Color::BrightWhite).on(Color::Magenta),
+                LogLevel::Fatal => style(Color::BrightWhite).on(Color::BrightRed),
+            }
+        })
+    }
+
+    /// Replaces the style of `level`.
+    pub fn style(mut self, level: LogLevel, style: LevelStyle) -> Self {
+        self.styles[level.severity() as usize] = style;
+        self
+    }
+
+    /// Replaces the prefix of `level`, e.g. with an emoji.
+    pub fn prefix(mut self, level: LogLevel, prefix: &str) -> Self {
+        self.styles[level.severity() as usize].prefix = prefix.to_string();
+        self
+    }
+
+    /// Sets the `chrono` format string for timestamps.
+    pub fn timestamp_format(mut self, format: &str) -> Self {
+        self.timestamp_format = format.to_string();
+        self
+    }
+
+    /// Sets the text placed between structured fields.
+    pub fn field_separator(mut self, separator: &str) -> Self {
+        self.field_separator = separator.to_string();
+        self
+    }
+
+    /// Returns the style of `level`.
+    pub fn level_style(&self, level: LogLevel) -> &LevelStyle {
+        &self.styles[level.severity() as usize]
+    }
+
+    /// Returns the timestamp format.
+    pub fn get_timestamp_format(&self) -> &str {
+        &self.timestamp_format
+    }
+
+    /// Returns the field separator.
+    pub fn get_field_separator(&self) -> &str {
+        &self.field_separator
+    }
+}
+
+impl Default for LogTheme {
+    /// The classic sysx look: bold text in each level's `LogLevel::style` colour.
+    fn default() -> Self {
+        Self::from_fn(|level| LevelStyle::new(level, Some(level.style())))
+    }
+}
+
+/// Replaces the theme used for pretty output by every sink.
+pub fn set_theme(theme: LogTheme) {
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(theme);
+}
+
+/// Returns the current theme.
+pub fn theme() -> Arc<LogTheme> {
+    THEME.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Decides whether to colour output, following <https://no-color.org> and `CLICOLOR_FORCE`.
+///
+/// A non-empty `NO_COLOR` disables colour, then a `CLICOLOR_FORCE` other than `0` forces it;
+/// otherwise colour is used only if the output `is_terminal`.
+pub fn should_color(is_terminal: bool) -> bool {
+    let set = |name| env::var_os(name).is_some_and(|v| !v.is_empty());
+    if set("NO_COLOR") {
+        false
+    } else if set("CLICOLOR_FORCE") && env::var_os("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+        true
+    } else {
+        is_terminal
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+/// Typed value of a structured log field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+}
+
+impl fmt::Display for Value {
+    /// Writes the value as it appears in `key=value` output; strings are quoted
+    /// only when they are empty or contain spaces, quotes or `=`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(v) => write!(f, "{v}"),
+            Value::Int(v) => write!(f, "{v}"),
+            Value::UInt(v) => write!(f, "{v}"),
+            Value::Float(v) => write!(f, "{v}"),
+            Value::Str(v) if needs_quotes(v) => write!(f, "{v:?}"),
+            Value::Str(v) => f.write_str(v),
+        }
+    }
+}
+
+fn needs_quotes(s: &str) -> bool {
+    s.is_empty()
+        || s.chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '"' | '='))
+}
+
+/// Conversion into a field `Value`, used by `log!` for `key = value` fields.
+///
+/// Takes `&self` so logging a variable never moves it. Use `key = %expr` to log any
+/// `Display` type and `key = ?expr` for any `Debug` type.
+pub trait ToValue {
+    fn to_value(&self) -> Value;
+}
+
+impl<T: ToValue + ?Sized> ToValue for &T {
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+}
+
+impl ToValue for Value {
+    fn to_value(&self) -> Value {
+        self.clone()
+    }
+}
+
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> Value {
+        Value::Str(self.to_string())
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::Str(self.clone())
+    }
+}
+
+impl ToValue for char {
+    fn to_value(&self) -> Value {
+        Value::Str(self.to_string())
+    }
+}
+
+macro_rules! impl_to_value {
+    ($variant:ident as $target:ty: $($ty:ty),+) => {
+        $(impl ToValue for $ty {
+            fn to_value(&self) -> Value {
+                Value::$variant(*self as $target)
+            }
+        })+
+    };
+}
+
+impl_to_value!(Int as i64: i8, i16, i32, i64, isize);
+impl_to_value!(UInt as u64: u8, u16, u32, u64, usize);
+impl_to_value!(Float as f64: f32, f64);
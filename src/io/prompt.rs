@@ -0,0 +1,264 @@
+use std::{
+    fmt::Display,
+    io::{self, BufRead, IsTerminal, StdinLock, Stdout, Write},
+    str::FromStr,
+};
+
+use crate::{Result, SysxError, io::cmd::input_from};
+
+/// Interactive prompts reading answers from any `BufRead` source.
+///
+/// Use `Prompter::stdio()` for the terminal, or `Prompter::new` to script the answers
+/// in tests. Every prompt re-asks on invalid input and fails on end of input.
+pub struct Prompter<R, W> {
+    reader: R,
+    writer: W,
+    tty:    bool,
+}
+
+impl Prompter<StdinLock<'static>, Stdout> {
+    /// Creates a prompter reading from stdin and writing to stdout.
+    pub fn stdio() -> Self {
+        let stdin = io::stdin();
+        let tty = stdin.is_terminal();
+        Self {
+            reader: stdin.lock(),
+            writer: io::stdout(),
+            tty,
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Creates a prompter reading answers from `reader` and writing questions to `writer`.
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer,
+            tty: false,
+        }
+    }
+
+    fn say(&mut self, text: &str) -> Result<()> {
+        writeln!(self.writer, "{text}")?;
+        Ok(())
+    }
+
+    fn ask(&mut self, message: &str) -> Result<String> {
+        write!(self.writer, "{message} ")?;
+        self.writer.flush()?;
+
+        let mut answer = String::new();
+        if input_from(&mut self.reader, &mut answer)? == 0 {
+            return Err(SysxError::IoError(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "No more input while waiting for an answer",
+            )));
+        }
+        if answer.ends_with('\r') {
+            answer.pop();
+        }
+        Ok(answer)
+    }
+
+    /// Asks for a line of free text.
+    pub fn input(&mut self, message: &str) -> Result<String> {
+        self.ask(message)
+    }
+
+    /// Asks a yes/no question. An empty answer picks `default`, or re-asks if there is none.
+    pub fn confirm(&mut self, message: &str, default: Option<bool>) -> Result<bool> {
+        let hint = match default {
+            Some(true) => "[Y/n]",
+            Some(false) => "[y/N]",
+            None => "[y/n]",
+        };
+
+        loop {
+            let answer = self.ask(&format!("{message} {hint}"))?;
+            match (answer.trim().to_lowercase().as_str(), default) {
+                ("y" | "yes", _) => return Ok(true),
+                ("n" | "no", _) => return Ok(false),
+                ("", Some(default)) => return Ok(default),
+                _ => self.say("Please answer 'y' or 'n'.")?,
+            }
+        }
+    }
+
+    fn list_items<T: Display>(&mut self, items: &[T]) -> Result<()> {
+        for (i, item) in items.iter().enumerate() {
+            writeln!(self.writer, "  {}) {item}", i + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Asks to pick one of `items` by number. Returns the zero-based index.
+    pub fn select<T: Display>(&mut self, message: &str, items: &[T]) -> Result<usize> {
+        if items.is_empty() {
+            return Err(SysxError::InvalidSyntax("Nothing to select from".into()));
+        }
+
+        self.say(message)?;
+        self.list_items(items)?;
+        loop {
+            let answer = self.ask(&format!("Choose [1-{}]:", items.len()))?;
+            match answer.trim().parse::<usize>() {
+                Ok(n) if (1..=items.len()).contains(&n) => return Ok(n - 1),
+                _ => self.say(&format!("Please enter a number from 1 to {}.", items.len()))?,
+            }
+        }
+    }
+
+    /// Asks to pick any number of `items`, separated by commas or spaces.
+    /// Returns the zero-based indices in ascending order, without duplicates.
+    pub fn multi_select<T: Display>(&mut self, message: &str, items: &[T]) -> Result<Vec<usize>> {
+        self.say(message)?;
+        self.list_items(items)?;
+        'ask: loop {
+            let answer = self.ask("Choose (e.g. 1,3), empty for none:")?;
+
+            let mut picked = Vec::new();
+            for part in answer.split([',', ' ']).filter(|p| !p.trim().is_empty()) {
+                match part.trim().parse::<usize>() {
+                    Ok(n) if (1..=items.len()).contains(&n) => picked.push(n - 1),
+                    _ => {
+                        self.say(&format!("'{}' is not a valid choice.", part.trim()))?;
+                        continue 'ask;
+                    }
+                }
+            }
+
+            picked.sort_unstable();
+            picked.dedup();
+            return Ok(picked);
+        }
+    }
+
+    /// Asks for a value of type `T`, re-asking until it parses.
+    pub fn prompt<T>(&mut self, message: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        loop {
+            let answer = self.ask(message)?;
+            match answer.trim().parse::<T>() {
+                Ok(value) => return Ok(value),
+                Err(e) => self.say(&format!("Invalid value: {e}"))?,
+            }
+        }
+    }
+
+    /// Asks for text accepted by `validate`, showing its error message and re-asking otherwise.
+    pub fn validated<F>(&mut self, message: &str, mut validate: F) -> Result<String>
+    where
+        F: FnMut(&str) -> std::result::Result<(), String>,
+    {
+        loop {
+            let answer = self.ask(message)?;
+            match validate(&answer) {
+                Ok(()) => return Ok(answer),
+                Err(e) => self.say(&e)?,
+            }
+        }
+    }
+
+    /// Asks for a secret. When reading from a terminal, echo is disabled while typing.
+    pub fn password(&mut self, message: &str) -> Result<String> {
+        if !self.tty {
+            return self.ask(message);
+        }
+
+        let answer = {
+            let _echo = EchoGuard::disable()?;
+            self.ask(message)
+        };
+        // The newline typed by the user was not echoed.
+        writeln!(self.writer)?;
+        answer
+    }
+}
+
+/// Restores terminal echo on drop.
+struct EchoGuard {
+    #[cfg(unix)]
+    original: libc::termios,
+}
+
+impl EchoGuard {
+    #[cfg(unix)]
+    fn disable() -> Result<Self> {
+        // SAFETY: `termios` is plain old data, fully initialised by `tcgetattr` before use.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        // SAFETY: valid fd and pointer to a live `termios`.
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut silent = original;
+        silent.c_lflag &= !libc::ECHO;
+        // SAFETY: valid fd and pointer to a live `termios`.
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &silent) } != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        Ok(Self { original })
+    }
+
+    #[cfg(not(unix))]
+    fn disable() -> Result<Self> {
+        Ok(Self {})
+    }
+}
+
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        // SAFETY: restores the settings read in `disable` on the same fd.
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// Asks a yes/no question on the terminal, re-asking until answered.
+pub fn confirm(message: &str) -> Result<bool> {
+    Prompter::stdio().confirm(message, None)
+}
+
+/// Asks a yes/no question on the terminal; an empty answer picks `default`.
+pub fn confirm_or(message: &str, default: bool) -> Result<bool> {
+    Prompter::stdio().confirm(message, Some(default))
+}
+
+/// Asks to pick one of `items` on the terminal. Returns the zero-based index.
+pub fn select<T: Display>(message: &str, items: &[T]) -> Result<usize> {
+    Prompter::stdio().select(message, items)
+}
+
+/// Asks to pick any number of `items` on the terminal. Returns zero-based indices.
+pub fn multi_select<T: Display>(message: &str, items: &[T]) -> Result<Vec<usize>> {
+    Prompter::stdio().multi_select(message, items)
+}
+
+/// Asks for a value of type `T` on the terminal, re-asking until it parses.
+pub fn prompt<T>(message: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    Prompter::stdio().prompt(message)
+}
+
+/// Asks for text on the terminal until `validate` accepts it.
+pub fn validated<F>(message: &str, validate: F) -> Result<String>
+where
+    F: FnMut(&str) -> std::result::Result<(), String>,
+{
+    Prompter::stdio().validated(message, validate)
+}
+
+/// Asks for a secret on the terminal without echoing it.
+pub fn password(message: &str) -> Result<String> {
+    Prompter::stdio().password(message)
+}
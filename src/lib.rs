@@ -2,6 +2,7 @@ pub mod io {
     pub mod cmd;
     pub mod env;
     pub mod log;
+    pub mod prompt;
 }
 pub mod net {
     pub mod ipv4;
@@ -21,7 +22,9 @@ pub mod types {
     pub mod error;
 }
 pub mod time {
+    pub mod retry;
     pub mod sleep;
+    pub use retry::*;
     pub use sleep::*;
 }
 
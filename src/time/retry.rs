@@ -0,0 +1,219 @@
+use std::{
+    fmt,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{SleepError, SleepTime};
+use crate::{
+    Result,
+    SysxError,
+    io::cmd::{Cmd, CmdOutput},
+    utils::rand::random,
+};
+
+/// Default number of attempts made by a `RetryPolicy`.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay strategy between retry attempts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backoff {
+    /// The same delay before every retry.
+    Fixed(Duration),
+    /// `initial + step * (n - 1)` before the n-th retry.
+    Linear {
+        initial: Duration,
+        step:    Duration,
+    },
+    /// `initial * factor^(n - 1)` before the n-th retry, capped at `max`.
+    ///
+    /// With `jitter`, each delay is scaled by a random factor in `[0.5, 1.0]`.
+    Exponential {
+        initial: Duration,
+        factor:  f64,
+        max:     Duration,
+        jitter:  bool,
+    },
+}
+
+fn to_duration<T>(time: T) -> Result<Duration>
+where
+    T: TryInto<SleepTime>,
+    T::Error: Into<SleepError>,
+{
+    let time = time.try_into().map_err(Into::into)?;
+    Ok(time.to_duration())
+}
+
+impl Backoff {
+    /// Fixed delay. Accepts the same inputs as `time::sleep`.
+    pub fn fixed<T>(delay: T) -> Result<Self>
+    where
+        T: TryInto<SleepTime>,
+        T::Error: Into<SleepError>,
+    {
+        Ok(Backoff::Fixed(to_duration(delay)?))
+    }
+
+    /// Linearly growing delay. Accepts the same inputs as `time::sleep`.
+    pub fn linear<T, U>(initial: T, step: U) -> Result<Self>
+    where
+        T: TryInto<SleepTime>,
+        T::Error: Into<SleepError>,
+        U: TryInto<SleepTime>,
+        U::Error: Into<SleepError>,
+    {
+        Ok(Backoff::Linear {
+            initial: to_duration(initial)?,
+            step:    to_duration(step)?,
+        })
+    }
+
+    /// Doubling delay with jitter, capped at `max`. Accepts the same inputs as `time::sleep`.
+    pub fn exponential<T, U>(initial: T, max: U) -> Result<Self>
+    where
+        T: TryInto<SleepTime>,
+        T::Error: Into<SleepError>,
+        U: TryInto<SleepTime>,
+        U::Error: Into<SleepError>,
+    {
+        Ok(Backoff::Exponential {
+            initial: to_duration(initial)?,
+            factor:  2.0,
+            max:     to_duration(max)?,
+            jitter:  true,
+        })
+    }
+
+    /// Returns the delay before the `retry`-th retry (starting at 1).
+    pub fn delay(&self, retry: u32) -> Result<Duration> {
+        let scale = match *self {
+            Backoff::Exponential { jitter: true, .. } => random(0.5, 1.0)?,
+            _ => 1.0,
+        };
+        Ok(self.scaled_delay(retry, scale))
+    }
+
+    /// Like `delay`, with the jitter factor given explicitly.
+    fn scaled_delay(&self, retry: u32, scale: f64) -> Duration {
+        let n = retry.max(1) - 1;
+        match *self {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Linear { initial, step } => initial.saturating_add(step.saturating_mul(n)),
+            Backoff::Exponential {
+                initial,
+                factor,
+                max,
+                ..
+            } => {
+                let secs = initial.as_secs_f64() * factor.max(1.0).powf(n as f64);
+                let capped = secs.min(max.as_secs_f64());
+                // Near `Duration::MAX` the f64 round trip can overflow; fall back to the cap.
+                Duration::try_from_secs_f64(capped * scale).unwrap_or(max)
+            }
+        }
+    }
+}
+
+/// Retry policy for fallible operations and commands.
+///
+/// By default every error is retried, up to `DEFAULT_MAX_ATTEMPTS` attempts in total.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    backoff:      Backoff,
+    max_attempts: u32,
+    deadline:     Option<Duration>,
+    retry_if:     Arc<dyn Fn(&SysxError) -> bool + Send + Sync>,
+}
+
+impl RetryPolicy {
+    /// Creates a policy with the given backoff strategy.
+    pub fn new(backoff: Backoff) -> Self {
+        Self {
+            backoff,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            deadline: None,
+            retry_if: Arc::new(|_| true),
+        }
+    }
+
+    /// Sets the total number of attempts, including the first one.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Gives up once the total elapsed time would exceed `deadline`.
+    ///
+    /// Accepts the same inputs as `time::sleep`.
+    pub fn deadline<T>(mut self, deadline: T) -> Result<Self>
+    where
+        T: TryInto<SleepTime>,
+        T::Error: Into<SleepError>,
+    {
+        self.deadline = Some(to_duration(deadline)?);
+        Ok(self)
+    }
+
+    /// Only retries errors for which `predicate` returns `true`.
+    pub fn retry_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&SysxError) -> bool + Send + Sync + 'static,
+    {
+        self.retry_if = Arc::new(predicate);
+        self
+    }
+
+    /// Calls `op` until it succeeds, returning the last error once retries are exhausted.
+    pub fn run<T, F>(&self, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let started = Instant::now();
+        let mut attempt = 1;
+
+        loop {
+            let err = match op() {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+
+            if attempt >= self.max_attempts || !(self.retry_if)(&err) {
+                return Err(err);
+            }
+
+            // A failed jitter draw must not replace the operation's error.
+            let delay = self
+                .backoff
+                .delay(attempt)
+                .unwrap_or_else(|_| self.backoff.scaled_delay(attempt, 1.0));
+            if let Some(deadline) = self.deadline
+                && started
+                    .elapsed()
+                    .checked_add(delay)
+                    .is_none_or(|total| total > deadline)
+            {
+                return Err(err);
+            }
+
+            thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+
+    /// Runs `cmd` until it exits successfully; non-zero exits count as failures.
+    pub fn run_cmd(&self, cmd: &Cmd) -> Result<CmdOutput> {
+        self.run(|| cmd.run_checked())
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("backoff", &self.backoff)
+            .field("max_attempts", &self.max_attempts)
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,5 +1,11 @@
 pub use std::time::Duration;
-use std::{convert::TryFrom, num::TryFromIntError, str::FromStr, thread};
+use std::{
+    convert::{Infallible, TryFrom},
+    fmt,
+    num::TryFromIntError,
+    str::FromStr,
+    thread,
+};
 
 use thiserror::Error;
 
@@ -33,6 +39,24 @@ pub enum SleepError {
     IntConversion(#[from] TryFromIntError),
 }
 
+// Lets infallible conversions (`u64`, `Duration`) satisfy the `Into<SleepError>` bound.
+impl From<Infallible> for SleepError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+/// Time units understood by `SleepTime::from_str`, as (spellings, nanoseconds), largest
+/// first. The first spelling is the one used for display.
+const UNITS: &[(&[&str], u128)] = &[
+    (&["h", "hour", "hours"], 3_600_000_000_000),
+    (&["m", "min", "mins"], 60_000_000_000),
+    (&["s", "", "sec", "secs"], 1_000_000_000),
+    (&["ms", "msec"], 1_000_000),
+    (&["us", "usec"], 1_000),
+    (&["ns", "nsec"], 1),
+];
+
 /// Represents sleep time internally with nanosecond precision
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SleepTime {
@@ -140,19 +164,11 @@ impl FromStr for SleepTime {
         }
 
         // Calculate multiplier
-        let multiplier = match unit_part.as_str() {
-            "" | "s" | "sec" | "secs" => 1_000_000_000.0,
-            "ms" | "msec" => 1_000_000.0,
-            "us" | "usec" => 1_000.0,
-            "ns" | "nsec" => 1.0,
-            "m" | "min" | "mins" => 60.0 * 1_000_000_000.0,
-            "h" | "hour" | "hours" => 3600.0 * 1_000_000_000.0,
-            _ => {
-                return Err(SleepError::InvalidFormat(format!(
-                    "unknown unit: '{unit_part}'"
-                )));
-            }
-        };
+        let multiplier = UNITS
+            .iter()
+            .find(|(names, _)| names.contains(&unit_part.as_str()))
+            .map(|&(_, nanos)| nanos as f64)
+            .ok_or_else(|| SleepError::InvalidFormat(format!("unknown unit: '{unit_part}'")))?;
 
         let nanoseconds = num * multiplier;
 
@@ -183,3 +199,33 @@ impl SleepTime {
         ms.into()
     }
 }
+
+impl fmt::Display for SleepTime {
+    /// Formats in the largest fitting unit with up to two decimals, e.g. `1.5s`, `250ms`.
+    ///
+    /// The unit is picked after rounding, so `59.999s` is shown as `1m`, not `60s`. The
+    /// output parses back with `SleepTime::from_str`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Value in hundredths of `nanos`, rounded half up.
+        let hundredths = |nanos: u128| {
+            self.nanoseconds
+                .saturating_mul(100)
+                .saturating_add(nanos / 2)
+                / nanos
+        };
+        let (names, value) = UNITS
+            .iter()
+            .map(|&(names, nanos)| (names, hundredths(nanos)))
+            .find(|&(_, value)| value >= 100)
+            .unwrap_or((UNITS[UNITS.len() - 1].0, 0));
+
+        let value = format!("{}.{:02}", value / 100, value % 100);
+        let value = value.trim_end_matches('0').trim_end_matches('.');
+        write!(f, "{value}{}", names[0])
+    }
+}
+
+/// Formats a duration in a human unit, e.g. `1.5s` or `250ms`. See `SleepTime`'s `Display`.
+pub fn humanize(duration: Duration) -> String {
+    SleepTime::from(duration).to_string()
+}
@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 use anyhow::Error as AnyhowError;
 use rand::distr::uniform::Error as RandUniformError;
 use regex::Error as RegexError;
 use thiserror::Error;
 
+use crate::time::SleepError;
+
 /// Main error type for the sysx library.
 #[derive(Debug, Error)]
 pub enum SysxError {
@@ -14,6 +18,10 @@ pub enum SysxError {
     #[error("Nested generics not supported in type: {0}")]
     NestedGenerics(String),
 
+    /// Help output was requested on the command line (`-h` / `--help`).
+    #[error("{0}")]
+    HelpRequested(String),
+
     /// Environment variable not found.
     #[error("Environment variable not found: {0}")]
     EnvVarNotFound(String),
@@ -81,6 +89,48 @@ pub enum SysxError {
     /// Serialization error.
     #[error("Serialization error: {0}")]
     SerializationError(String),
+
+    /// Command did not finish before its deadline and was killed.
+    #[error("Command '{command}' timed out after {timeout:?}")]
+    CommandTimeout {
+        /// Command line that was executed.
+        command: String,
+        /// Deadline that was exceeded.
+        timeout: Duration,
+        /// Stdout captured before the command was killed.
+        stdout:  Vec<u8>,
+        /// Stderr captured before the command was killed.
+        stderr:  Vec<u8>,
+    },
+
+    /// Command exited unsuccessfully.
+    #[error("Command '{command}' failed ({}){}", describe_exit(*.code, *.signal), describe_stderr(.stderr))]
+    CommandFailed {
+        /// Command line that was executed.
+        command: String,
+        /// Exit code, if the command exited normally.
+        code:    Option<i32>,
+        /// Terminating signal, if the command was killed (Unix only).
+        signal:  Option<i32>,
+        /// Last lines of the command's stderr.
+        stderr:  String,
+    },
+}
+
+fn describe_exit(code: Option<i32>, signal: Option<i32>) -> String {
+    match (code, signal) {
+        (Some(code), _) => format!("exit code {code}"),
+        (None, Some(signal)) => format!("killed by signal {signal}"),
+        (None, None) => "terminated".to_string(),
+    }
+}
+
+fn describe_stderr(stderr: &str) -> String {
+    if stderr.is_empty() {
+        String::new()
+    } else {
+        format!(": {stderr}")
+    }
 }
 
 /// Errors for time-based operations.
@@ -99,6 +149,17 @@ pub enum TimeError {
     NegativeDuration,
 }
 
+impl From<SleepError> for SysxError {
+    fn from(err: SleepError) -> Self {
+        let time_err = match err {
+            SleepError::InvalidFormat(s) => TimeError::InvalidFormat(s),
+            SleepError::NegativeTime(_) => TimeError::NegativeDuration,
+            SleepError::OutOfRange(_) | SleepError::IntConversion(_) => TimeError::OutOfRange,
+        };
+        SysxError::TimeError(time_err)
+    }
+}
+
 /// Result type for sysx library functions.
 pub type Result<T> = std::result::Result<T, SysxError>;
 
@@ -67,3 +67,333 @@ fn test_command_macros() {
     assert_eq!(stdout.trim(), "hello world"); // Check stdout from the returned Output
     assert!(stderr.is_empty(), "Stderr was not empty: {stderr}");
 }
+
+#[test]
+fn test_command_builder() {
+    // argv construction, stdin data and environment
+    let output = Cmd::new("sh")
+        .args(["-c", "cat; printf ' %s' \"$SYSX_TEST_VAR\""])
+        .env("SYSX_TEST_VAR", "from env")
+        .stdin_data("piped input")
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "Command failed: {:?}",
+        output.status
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "piped input from env"
+    );
+
+    // working directory
+    let dir = tempfile::tempdir().unwrap();
+    let output = Cmd::new("pwd").current_dir(dir.path()).output().unwrap();
+    let cwd = std::path::PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    assert_eq!(
+        cwd.canonicalize().unwrap(),
+        dir.path().canonicalize().unwrap()
+    );
+
+    // cleared environment
+    let output = Cmd::parse("sh -c 'printf %s \"$HOME\"'")
+        .unwrap()
+        .env_clear()
+        .output()
+        .unwrap();
+    assert!(output.stdout.is_empty());
+
+    // inherited streams are not captured
+    let output = Cmd::parse("echo inherited")
+        .unwrap()
+        .inherit_output()
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    assert!(Cmd::parse("   ").is_err());
+    assert!(Cmd::from_argv(Vec::<String>::new()).is_err());
+    assert_eq!(Cmd::new("echo").arg("a b").command_line(), "echo 'a b'");
+}
+
+#[test]
+fn test_command_timeout() {
+    let output = slrun_timeout("echo fast", "5s").unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "fast");
+
+    let started = std::time::Instant::now();
+    let err = Cmd::new("sh")
+        .args(["-c", "echo partial; exec sleep 10"])
+        .timeout(300u64)
+        .unwrap()
+        .output()
+        .unwrap_err();
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+    match err {
+        SysxError::CommandTimeout {
+            timeout, stdout, ..
+        } => {
+            assert_eq!(timeout, std::time::Duration::from_millis(300));
+            assert_eq!(String::from_utf8_lossy(&stdout).trim(), "partial");
+        }
+        other => panic!("Expected CommandTimeout, got {other:?}"),
+    }
+
+    assert!(Cmd::new("true").timeout("soon").is_err());
+
+    // Descendants of a timed-out command are stopped too, so nothing keeps its pipes open.
+    let dir = tempfile::tempdir().unwrap();
+    let pid_file = dir.path().join("pid");
+    let line = format!(
+        "sh -c 'sleep 30 & echo $! > {}; wait; echo done'",
+        pid_file.display()
+    );
+    let started = std::time::Instant::now();
+    assert!(matches!(
+        slrun_timeout(&line, "200ms").unwrap_err(),
+        SysxError::CommandTimeout { .. }
+    ));
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    #[cfg(target_os = "linux")]
+    {
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        // The orphaned `sleep` is reaped by init, so allow a moment and accept a zombie.
+        let alive = || {
+            std::fs::read_to_string(&stat).is_ok_and(|stat| {
+                stat.rsplit(')')
+                    .next()
+                    .unwrap()
+                    .trim_start()
+                    .starts_with(|c| c != 'Z')
+            })
+        };
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(1);
+        while alive() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(!alive(), "sleep {} survived the timeout", pid.trim());
+    }
+}
+
+#[test]
+fn test_command_pipeline() {
+    let result = slrun_pipeline("printf 'b\\na\\nc\\n' | sort | head -n 2").unwrap();
+    assert!(result.success());
+    assert_eq!(result.stages.len(), 3);
+    assert_eq!(String::from_utf8_lossy(&result.output.stdout), "a\nb\n");
+
+    // `&&` / `||` short-circuit and quoted operators stay literal
+    let result = slrun_pipeline("false && echo skipped || echo 'a|b && c'").unwrap();
+    assert!(result.success());
+    assert_eq!(result.stages.len(), 2);
+    assert!(!result.stages[0].status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&result.output.stdout).trim(),
+        "a|b && c"
+    );
+
+    // redirects
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("out.txt");
+    let line = format!("echo one > {0} && echo two >> {0}", file.display());
+    let result = slrun_pipeline(&line).unwrap();
+    assert!(result.output.stdout.is_empty());
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "one\ntwo\n");
+
+    let result = Pipeline::new(Cmd::new("tr").args(["a-z", "A-Z"]))
+        .stdin_file(&file)
+        .run()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&result.output.stdout), "ONE\nTWO\n");
+
+    assert!(matches!(
+        Pipeline::parse("echo a |").unwrap_err(),
+        SysxError::InvalidSyntax(_)
+    ));
+    assert!(matches!(
+        Pipeline::parse("echo a > out | cat").unwrap_err(),
+        SysxError::InvalidSyntax(_)
+    ));
+    assert!(Pipeline::parse("sleep 1 &").is_err());
+
+    // A redirect that fails to open still kills and reaps the stages already started.
+    let pid_file = dir.path().join("pid");
+    let line = format!(
+        "sh -c 'echo $$ > {}; exec sleep 30' | cat > {}",
+        pid_file.display(),
+        dir.path().join("missing/out.txt").display()
+    );
+    let started = std::time::Instant::now();
+    assert!(slrun_pipeline(&line).is_err());
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    #[cfg(target_os = "linux")]
+    if let Ok(pid) = std::fs::read_to_string(&pid_file) {
+        assert!(!std::path::Path::new(&format!("/proc/{}", pid.trim())).exists());
+    }
+}
+
+#[test]
+fn test_command_streaming() {
+    let mut stream = stream_lines("sh -c 'echo out1; echo err1 >&2; echo out2'").unwrap();
+    let lines: Vec<Line> = stream.by_ref().collect();
+    assert!(stream.wait().unwrap().success());
+
+    let stdout: Vec<&str> = lines
+        .iter()
+        .filter(|l| l.stream == StreamKind::Stdout)
+        .map(|l| l.text.as_str())
+        .collect();
+    assert_eq!(stdout, ["out1", "out2"]);
+    assert!(lines.contains(&Line {
+        stream: StreamKind::Stderr,
+        text:   "err1".into(),
+    }));
+
+    let mut seen = Vec::new();
+    let output = Cmd::parse("printf 'a\\nb'")
+        .unwrap()
+        .for_each_line(|line| seen.push(line.text.clone()))
+        .unwrap();
+    assert_eq!(seen, ["a", "b"]);
+    assert_eq!(output.stdout, b"a\nb");
+
+    let output = run_tee("echo tee").unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "tee");
+
+    // the timeout ends iteration and is reported by `wait` and `for_each_line`
+    let slow = Cmd::new("sh")
+        .args(["-c", "echo first; exec sleep 10"])
+        .timeout(300u64)
+        .unwrap();
+    let started = std::time::Instant::now();
+    let mut stream = slow.stream().unwrap();
+    assert_eq!(stream.next().unwrap().text, "first");
+    assert!(stream.next().is_none());
+    assert!(matches!(
+        stream.wait().unwrap_err(),
+        SysxError::CommandTimeout { .. }
+    ));
+    match slow.for_each_line(|_| {}).unwrap_err() {
+        SysxError::CommandTimeout { stdout, .. } => assert_eq!(stdout, b"first\n"),
+        other => panic!("Expected CommandTimeout, got {other:?}"),
+    }
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+    // dropping a stream kills and reaps the child
+    let stream = stream_lines("sleep 10").unwrap();
+    let pid = stream.id();
+    drop(stream);
+    #[cfg(target_os = "linux")]
+    assert!(!std::path::Path::new(&format!("/proc/{pid}")).exists());
+}
+
+#[test]
+fn test_command_checked() {
+    let output = slrun_checked("printf 'one\\ntwo\\n'").unwrap();
+    assert_eq!(output.exit_code(), Some(0));
+    assert_eq!(output.signal(), None);
+    assert_eq!(output.stdout_str(), "one\ntwo\n");
+    assert_eq!(output.lines(), ["one", "two"]);
+
+    let err = slrun_checked("sh -c 'echo boom >&2; exit 3'").unwrap_err();
+    match err {
+        SysxError::CommandFailed {
+            ref command,
+            code,
+            ref stderr,
+            ..
+        } => {
+            assert!(command.starts_with("sh -c"));
+            assert_eq!(code, Some(3));
+            assert_eq!(stderr, "boom");
+        }
+        ref other => panic!("Expected CommandFailed, got {other:?}"),
+    }
+    assert!(err.to_string().ends_with("failed (exit code 3): boom"));
+
+    let output = Cmd::new("false").capture().unwrap();
+    assert!(!output.success());
+    assert_eq!(output.exit_code(), Some(1));
+}
+
+#[test]
+fn test_command_spawn() {
+    let mut process = spawn("sh -c 'echo started; exec sleep 10'").unwrap();
+    assert!(process.try_wait().unwrap().is_none());
+    assert!(process.wait_with_timeout(50u64).unwrap().is_none());
+
+    let mut started = Vec::new();
+    for _ in 0..100 {
+        started.extend(process.read_stdout());
+        if !started.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(String::from_utf8_lossy(&started).trim(), "started");
+    assert!(process.read_stdout().is_empty());
+
+    process.kill().unwrap();
+    assert!(!process.try_wait().unwrap().unwrap().success());
+
+    #[cfg(unix)]
+    {
+        let mut process = spawn("sleep 10").unwrap();
+        process.signal(15).unwrap();
+        let output = process.wait_with_output().unwrap();
+        assert_eq!(output.signal(), Some(15));
+    }
+
+    let output = Cmd::new("echo")
+        .arg("done")
+        .spawn()
+        .unwrap()
+        .wait_with_output()
+        .unwrap();
+    assert_eq!(output.stdout_str().trim(), "done");
+
+    // dropping a handle kills the child by default
+    let process = spawn("sleep 10").unwrap();
+    let pid = process.id();
+    drop(process);
+    #[cfg(target_os = "linux")]
+    assert!(!std::path::Path::new(&format!("/proc/{pid}")).exists());
+
+    let process = spawn("true").unwrap().on_drop(DropBehavior::Detach);
+    drop(process);
+}
+
+#[test]
+fn test_command_jobs() {
+    let lines: Vec<String> = (0..8)
+        .map(|i| format!("sh -c 'sleep 0.0{i}; echo {i}'"))
+        .collect();
+    let results = run_parallel(&lines, 3);
+
+    assert_eq!(results.len(), 8);
+    for (i, job) in results.iter().enumerate() {
+        assert!(job.success(), "Job {i} failed: {job:?}");
+        let output = job.result.as_ref().unwrap().as_ref().unwrap();
+        assert_eq!(output.stdout_str().trim(), i.to_string());
+    }
+
+    let results =
+        JobRunner::new(1)
+            .fail_fast(true)
+            .run_lines(&["true", "false", "'unterminated", "true"]);
+    assert!(results[0].success());
+    assert!(matches!(
+        results[1].result,
+        Some(Err(SysxError::CommandFailed { .. }))
+    ));
+    assert!(results[2].skipped() && results[3].skipped());
+
+    let results = JobRunner::default().run([Cmd::new("true"), Cmd::new("false")]);
+    assert!(results[0].success() && !results[1].success() && !results[1].skipped());
+}
@@ -8,3 +8,237 @@ fn test_arguments_handling() {
     assert!(!full_args.is_empty());
     assert_eq!(args.len(), full_args.len() - 1);
 }
+
+#[test]
+fn test_argument_parser() {
+    let parser = ArgParser::new("tool")
+        .about("Does things")
+        .arg(Arg::flag("verbose").short('v').help("More output"))
+        .arg(Arg::option("level").short('l').default("3"))
+        .arg(Arg::option("include").short('I').multiple(true))
+        .arg(Arg::positional("input").required(true))
+        .arg(Arg::positional("rest").multiple(true))
+        .subcommand(ArgParser::new("run").arg(Arg::flag("dry-run")));
+
+    let m = parser
+        .parse([
+            "-vv",
+            "--include=a",
+            "-Ib",
+            "-l",
+            "7",
+            "in.txt",
+            "--",
+            "-x",
+            "y",
+        ])
+        .unwrap();
+    assert_eq!(m.count("verbose"), 2);
+    assert_eq!(m.get::<u8>("level").unwrap(), Some(7));
+    assert_eq!(m.values("include"), ["a", "b"]);
+    assert_eq!(m.value("input"), Some("in.txt"));
+    assert_eq!(m.values("rest"), ["-x", "y"]);
+
+    let m = parser.parse(["in.txt", "run", "--dry-run"]).unwrap();
+    assert_eq!(m.get::<u8>("level").unwrap(), Some(3));
+    let (name, sub) = m.subcommand().unwrap();
+    assert_eq!(name, "run");
+    assert!(sub.flag("dry-run"));
+
+    assert!(matches!(
+        parser.parse(["--help"]),
+        Err(sysx::SysxError::HelpRequested(help)) if help.contains("--verbose")
+    ));
+    assert!(parser.parse(["--unknown", "in.txt"]).is_err());
+    assert!(parser.parse(["-l"]).is_err());
+    assert!(parser.parse(Vec::<String>::new()).is_err()); // missing required input
+    assert!(
+        parser
+            .parse(["-l", "many", "in.txt"])
+            .unwrap()
+            .get::<u8>("level")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_typed_variables() {
+    // SAFETY: every test in this file that touches the environment holds `env_lock`.
+    let _env = unsafe { EnvGuard::new() }
+        .set("SYSX_TYPED_PORT", "8080")
+        .set("SYSX_TYPED_FLAG", "Yes")
+        .set("SYSX_TYPED_WAIT", "1.5s")
+        .set("SYSX_TYPED_LIST", "a, b,,c ")
+        .set("SYSX_TYPED_BAD", "eighty");
+
+    assert_eq!(get_var("SYSX_TYPED_PORT").unwrap(), "8080");
+    assert_eq!(get_var_parsed::<u16>("SYSX_TYPED_PORT").unwrap(), 8080);
+    assert_eq!(get_var_or("SYSX_TYPED_MISSING", "fallback"), "fallback");
+    assert!(get_var_bool("SYSX_TYPED_FLAG").unwrap());
+    assert_eq!(
+        get_var_duration("SYSX_TYPED_WAIT").unwrap(),
+        std::time::Duration::from_millis(1500)
+    );
+    assert_eq!(get_var_list("SYSX_TYPED_LIST").unwrap(), ["a", "b", "c"]);
+    assert_eq!(vars_with_prefix("SYSX_TYPED_").len(), 5);
+
+    assert!(matches!(
+        get_var("SYSX_TYPED_MISSING"),
+        Err(sysx::SysxError::EnvVarNotFound(name)) if name == "SYSX_TYPED_MISSING"
+    ));
+    match get_var_parsed::<u16>("SYSX_TYPED_BAD") {
+        Err(sysx::SysxError::ValidationError { context, .. }) => {
+            assert!(context.unwrap().contains("SYSX_TYPED_BAD"));
+        }
+        other => panic!("Expected ValidationError, got {other:?}"),
+    }
+    assert!(get_var_bool("SYSX_TYPED_BAD").is_err());
+}
+
+#[test]
+fn test_dotenv_parsing() {
+    let source = r#"
+# comment
+export HOST=localhost
+PORT = 8080 # trailing comment
+URL="http://${HOST}:${PORT}/"
+LITERAL='${HOST} \n'
+ESCAPED="line\n\"quoted\" \${HOST}"
+FALLBACK=${SYSX_DOTENV_UNSET:-default value}
+EMPTY=
+"#;
+    // Interpolation falls back to the process environment.
+    let values = {
+        let _lock = env_lock();
+        parse_dotenv(source).unwrap()
+    };
+    assert_eq!(values["HOST"], "localhost");
+    assert_eq!(values["PORT"], "8080");
+    assert_eq!(values["URL"], "http://localhost:8080/");
+    assert_eq!(values["LITERAL"], "${HOST} \\n");
+    assert_eq!(values["ESCAPED"], "line\n\"quoted\" ${HOST}");
+    assert_eq!(values["FALLBACK"], "default value");
+    assert_eq!(values["EMPTY"], "");
+
+    let err = parse_dotenv("OK=1\nBROKEN \"value\"\n").unwrap_err();
+    assert!(err.to_string().contains("line 2, column 8"), "{err}");
+    assert!(parse_dotenv("KEY=\"unterminated").is_err());
+}
+
+#[test]
+fn test_dotenv_loading() {
+    let _lock = env_lock();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".env");
+    std::fs::write(&path, "SYSX_DOTENV_KEPT=file\nSYSX_DOTENV_NEW=file\n").unwrap();
+
+    // SAFETY: every test in this file that touches the environment holds `env_lock`.
+    let _env = unsafe { EnvGuard::new() }
+        .set("SYSX_DOTENV_KEPT", "process")
+        .remove("SYSX_DOTENV_NEW");
+
+    let values = read_dotenv(&path).unwrap();
+    assert_eq!(values.len(), 2);
+    assert!(std::env::var("SYSX_DOTENV_NEW").is_err());
+
+    // SAFETY: every test in this file that touches the environment holds `env_lock`.
+    unsafe { load_dotenv(&path) }.unwrap();
+    assert_eq!(get_var("SYSX_DOTENV_KEPT").unwrap(), "process");
+    assert_eq!(get_var("SYSX_DOTENV_NEW").unwrap(), "file");
+
+    // SAFETY: as above.
+    unsafe { load_dotenv_override(&path) }.unwrap();
+    assert_eq!(get_var("SYSX_DOTENV_KEPT").unwrap(), "file");
+
+    assert!(read_dotenv(dir.path().join("missing.env")).is_err());
+}
+
+#[test]
+fn test_env_guard() {
+    // SAFETY (all guards below): every test in this file that touches the environment
+    // holds `env_lock`.
+    {
+        let _lock = env_lock();
+        {
+            let _env = unsafe { EnvGuard::new() }
+                .set("SYSX_GUARD_SET", "outer")
+                .remove("SYSX_GUARD_SET")
+                .set("SYSX_GUARD_SET", "inner");
+            assert_eq!(get_var("SYSX_GUARD_SET").unwrap(), "inner");
+
+            // The lock is reentrant, so guards nest on one thread.
+            {
+                let _nested = unsafe { EnvGuard::new() }.set("SYSX_GUARD_SET", "nested");
+                assert_eq!(get_var("SYSX_GUARD_SET").unwrap(), "nested");
+            }
+            assert_eq!(get_var("SYSX_GUARD_SET").unwrap(), "inner");
+        }
+        assert!(get_var("SYSX_GUARD_SET").is_err());
+
+        let seen = unsafe {
+            with_env(
+                [("SYSX_GUARD_A", Some("1")), ("SYSX_GUARD_B", None)],
+                || (get_var("SYSX_GUARD_A").ok(), get_var("SYSX_GUARD_B").ok()),
+            )
+        };
+        assert_eq!(seen, (Some("1".to_string()), None));
+        assert!(get_var("SYSX_GUARD_A").is_err());
+    }
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let value = i.to_string();
+                let _env = unsafe { EnvGuard::new() }.set("SYSX_GUARD_SHARED", &value);
+                std::thread::yield_now();
+                assert_eq!(get_var("SYSX_GUARD_SHARED").unwrap(), value);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let _lock = env_lock();
+    assert!(get_var("SYSX_GUARD_SHARED").is_err());
+}
+
+#[test]
+fn test_standard_directories() {
+    let _lock = env_lock();
+    let root = tempfile::tempdir().unwrap();
+    let home = root.path().join("home");
+    let data = root.path().join("data");
+
+    // SAFETY: every test in this file that touches the environment holds `env_lock`.
+    let _env = unsafe { EnvGuard::new() }
+        .set("HOME", &home)
+        .set("USERPROFILE", &home)
+        .remove("XDG_CONFIG_HOME")
+        .remove("APPDATA")
+        .set("XDG_DATA_HOME", &data)
+        .set("XDG_CACHE_HOME", "relative/cache")
+        .remove("XDG_RUNTIME_DIR");
+
+    assert_eq!(home_dir().unwrap(), home);
+    assert_eq!(config_dir().unwrap(), home.join(".config"));
+    assert_eq!(data_dir().unwrap(), data);
+    if cfg!(unix) {
+        // Relative XDG values are ignored.
+        assert_eq!(cache_dir().unwrap(), home.join(".cache"));
+        assert_eq!(state_dir().unwrap(), home.join(".local/state"));
+    }
+    assert_eq!(runtime_dir(), None);
+
+    let app = AppDirs::new("sysx-test").unwrap();
+    assert_eq!(app.data().unwrap(), data.join("sysx-test"));
+    assert!(!data.exists());
+    assert_eq!(app.runtime().unwrap(), None);
+
+    let config = app.create(true).config().unwrap();
+    assert_eq!(config, home.join(".config").join("sysx-test"));
+    assert!(config.is_dir());
+
+    assert!(AppDirs::new("").is_err());
+    assert!(AppDirs::new("../escape").is_err());
+    assert!(AppDirs::new("a/b").is_err());
+}
@@ -1,9 +1,32 @@
-use sysx::io::log::*;
+use std::sync::{Mutex, MutexGuard};
+
+use sysx::io::{
+    env::{EnvGuard, env_lock},
+    log::*,
+};
+
+/// Serialises tests that replace the global logger.
+fn global_logger() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// `tempfile` reads `TMPDIR`, so it must not race the tests that change the environment.
+fn tempdir() -> tempfile::TempDir {
+    let _env = env_lock();
+    tempfile::tempdir().unwrap()
+}
 
 #[test]
 fn test_log_levels() {
     assert_eq!(LogLevel::Info.style(), Color::Blue);
     assert_eq!(log_level!(WARNING), LogLevel::Warning);
+    assert_eq!(log_level!(Warning), LogLevel::Warning);
+    assert_eq!(log_level!(warn), LogLevel::Warning);
+    assert_eq!(log_level!(ERR), LogLevel::Error);
+    assert_eq!(log_level!(Dbg), LogLevel::Debug);
+    const FATAL: LogLevel = log_level!(critical);
+    assert_eq!(FATAL, LogLevel::Fatal);
 }
 
 #[test]
@@ -11,3 +34,553 @@ fn test_log_formatting() {
     let styled = style!("test", LogLevel::Warning);
     assert_eq!(styled.fgcolor, Some(Color::Yellow));
 }
+
+#[test]
+fn test_logger_filtering() {
+    let memory = MemorySink::new();
+    let logger = Logger::new()
+        .level(LogLevel::Warning)
+        .module_level("app::net", LogLevel::Trace)
+        .module_level("app::net::tls", LogLevel::Error)
+        .sink(memory.clone());
+
+    let record = |level, module: &str, message: &str| {
+        Record::new(level, message, None, module, file!(), line!())
+    };
+    logger.log(&record(LogLevel::Info, "app", "hidden"));
+    logger.log(&record(LogLevel::Error, "app", "shown"));
+    logger.log(&record(LogLevel::Trace, "app::net", "net trace"));
+    logger.log(&record(LogLevel::Debug, "app::network", "not a submodule"));
+    logger.log(&record(LogLevel::Warning, "app::net::tls", "tls warning"));
+    logger.log(&record(LogLevel::Bug, "app::net::tls::x", "tls bug"));
+
+    assert_eq!(memory.messages(), ["shown", "net trace", "tls bug"]);
+    assert!(logger.enabled(LogLevel::Fatal, "app::net::tls"));
+    assert_eq!(logger.level_for("other"), LogLevel::Warning);
+}
+
+#[test]
+fn test_global_logger_and_file_sink() {
+    let dir = tempdir();
+    let path = dir.path().join("app.log");
+    let _lock = global_logger();
+    let memory = MemorySink::new();
+    set_logger(
+        Logger::new()
+            .level(LogLevel::Info)
+            .sink(memory.clone())
+            .sink(FileSink::open(&path).unwrap()),
+    );
+
+    log!(DEBUG, "filtered {}", 1);
+    log!(INFO, "written {}", 2);
+
+    let records = memory.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].message, "written 2");
+    assert_eq!(records[0].module, module_path!());
+    assert!(records[0].file.ends_with("logging.rs"));
+
+    flush();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("[INFO] written 2\n"), "{text}");
+    assert!(
+        !text.contains("\u{1b}["),
+        "file output must not be coloured"
+    );
+}
+
+#[test]
+fn test_level_ordering_and_parsing() {
+    assert!(LogLevel::Trace < LogLevel::Debug);
+    assert!(LogLevel::Info < LogLevel::Success);
+    assert!(LogLevel::Warning < LogLevel::Error);
+    assert!(LogLevel::Bug < LogLevel::Fatal);
+    assert_eq!(
+        [LogLevel::Fatal, LogLevel::Trace, LogLevel::Warning]
+            .iter()
+            .max(),
+        Some(&LogLevel::Fatal)
+    );
+
+    assert_eq!("warn".parse::<LogLevel>().unwrap(), LogLevel::Warning);
+    assert_eq!(" Error ".parse::<LogLevel>().unwrap(), LogLevel::Error);
+    assert_eq!("DBG".parse::<LogLevel>().unwrap(), LogLevel::Debug);
+    assert!("loud".parse::<LogLevel>().is_err());
+    assert_eq!(LogLevel::Warning.to_string(), "WARNING");
+    assert_eq!(format!("[{:<7}]", LogLevel::Info), "[INFO   ]");
+}
+
+#[test]
+fn test_log_filter_directives() {
+    let logger = Logger::new()
+        .filter("warn, app::net=trace ,app::db")
+        .unwrap();
+    assert_eq!(logger.level_for("app"), LogLevel::Warning);
+    assert_eq!(logger.level_for("app::net::tls"), LogLevel::Trace);
+    assert_eq!(logger.level_for("app::db"), LogLevel::Trace);
+    assert!(!logger.enabled(LogLevel::Info, "app"));
+
+    assert!(Logger::new().filter("app=loud").is_err());
+    assert!(Logger::new().filter("=info").is_err());
+
+    let logger = {
+        // SAFETY: every test in this file that touches the environment holds `env_lock`.
+        let _env = unsafe { EnvGuard::new() }.set(LOG_ENV, "error,app=debug");
+        Logger::new().env_filter().unwrap()
+    };
+    assert_eq!(logger.level_for("other"), LogLevel::Error);
+    assert_eq!(logger.level_for("app"), LogLevel::Debug);
+}
+
+#[test]
+fn test_structured_fields() {
+    let fields = || {
+        let peer = String::from("10.0.0.7:443");
+        let retries = 3u8;
+        let record = Record::new(LogLevel::Info, "connected", None, "app", file!(), line!())
+            .with_fields(vec![
+                ("peer".into(), peer.to_value()),
+                ("retries".into(), retries.to_value()),
+                ("note".into(), "two words".to_value()),
+            ]);
+        // `peer` is still usable: fields borrow their values.
+        assert_eq!(peer.len(), 12);
+        record
+    };
+
+    let record = fields();
+    assert_eq!(record.field("retries"), Some(&Value::UInt(3)));
+    assert_eq!(
+        format_pretty(&record, false).split_once(' ').unwrap().1,
+        format!(
+            "{} [INFO] connected peer=10.0.0.7:443 retries=3 note=\"two words\"",
+            record.timestamp.format("%H:%M:%S%.3f")
+        )
+    );
+
+    let _lock = global_logger();
+    let memory = MemorySink::new();
+    set_logger(Logger::new().sink(memory.clone()));
+
+    let peer = "10.0.0.7:443";
+    log!(INFO, "plain {}", 1);
+    log!(ERROR, "with context"; "while fetching {}");
+    log!(INFO, "connected to {}", "db"; peer = peer, retries = 3, ok = true);
+    log!(WARNING, "slow"; "retrying"; elapsed = %1.5, status = ?Some(503),);
+
+    let records = memory.records();
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[1].context.as_deref(), Some("while fetching {}"));
+    assert!(records[1].fields.is_empty());
+    assert_eq!(records[2].context, None);
+    assert_eq!(records[2].field("peer"), Some(&Value::Str(peer.into())));
+    assert_eq!(records[2].field("retries"), Some(&Value::Int(3)));
+    assert_eq!(records[2].field("ok"), Some(&Value::Bool(true)));
+    assert_eq!(records[3].context.as_deref(), Some("retrying"));
+    assert_eq!(records[3].field("elapsed"), Some(&Value::Str("1.5".into())));
+    assert_eq!(
+        records[3].field("status"),
+        Some(&Value::Str("Some(503)".into()))
+    );
+
+    // Long argument lists must not hit the macro recursion limit.
+    struct Inner {
+        b: Vec<u8>,
+    }
+    struct Outer {
+        a: Inner,
+    }
+    let s = Outer {
+        a: Inner { b: vec![1, 2] },
+    };
+    log!(
+        INFO,
+        "{} {} {} {} {} {} {} {} {} {} {} {} {} {} {total}",
+        s.a.b.len(), s.a.b.len(), s.a.b.len(), s.a.b.len(), s.a.b.len(), s.a.b.len(),
+        s.a.b.len(), s.a.b.len(), s.a.b.len(), s.a.b.len(), s.a.b.len(), s.a.b.len(),
+        s.a.b.len(), s.a.b.len(),
+        total = s.a.b.len() * 14;
+        "long"; count = s.a.b.len()
+    );
+    let records = memory.records();
+    assert_eq!(records[4].message, format!("{}28", "2 ".repeat(14)));
+    assert_eq!(records[4].context.as_deref(), Some("long"));
+    assert_eq!(records[4].field("count"), Some(&Value::UInt(2)));
+}
+
+#[test]
+fn test_machine_readable_formats() {
+    let mut record = Record::new(
+        LogLevel::Warning,
+        "slow \"reply\"",
+        Some("line 1\nline 2".into()),
+        "app::net",
+        "src/net.rs",
+        42,
+    )
+    .with_fields(vec![
+        ("peer".into(), Value::Str("10.0.0.7".into())),
+        ("retries".into(), Value::Int(-1)),
+        ("ok".into(), Value::Bool(false)),
+        ("ratio".into(), Value::Float(f64::NAN)),
+    ]);
+    record.timestamp = "2024-05-01T12:30:00.250+02:00"
+        .parse::<chrono::DateTime<chrono::FixedOffset>>()
+        .unwrap()
+        .with_timezone(&Local);
+    let ts = record.timestamp.to_rfc3339();
+
+    assert_eq!(
+        Format::Json.render(&record, true),
+        format!(
+            r#"{{"timestamp":"{ts}","level":"WARNING","message":"slow \"reply\"","context":"line 1\nline 2","module":"app::net","file":"src/net.rs:42","fields":{{"peer":"10.0.0.7","retries":-1,"ok":false,"ratio":null}}}}"#
+        )
+    );
+    assert_eq!(
+        Format::Logfmt.render(&record, true),
+        format!(
+            r#"ts={ts} level=warning msg="slow \"reply\"" ctx="line 1\nline 2" module=app::net file=src/net.rs:42 peer=10.0.0.7 retries=-1 ok=false ratio=NaN"#
+        )
+    );
+    assert!(!Format::Pretty.render(&record, false).contains('\u{1b}'));
+
+    let dir = tempdir();
+    let path = dir.path().join("app.jsonl");
+    let sink = FileSink::open(&path).unwrap().format(Format::Json);
+    sink.write(&record).unwrap();
+    sink.write(&record).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(text.lines().count(), 2);
+    assert!(text.lines().all(|line| line.starts_with("{\"timestamp\"")));
+}
+
+#[test]
+fn test_rotating_file_sink() {
+    let dir = tempdir();
+    let path = dir.path().join("logs/app.log");
+    let record = |message: &str| Record::new(LogLevel::Info, message, None, "app", "app.rs", 1);
+
+    let sink = RotatingFileSink::open(&path)
+        .unwrap()
+        .format(Format::Logfmt)
+        .max_size(200)
+        .keep(2);
+    for i in 0..10 {
+        sink.write(&record(&format!("message {i}"))).unwrap();
+    }
+    sink.flush().unwrap();
+
+    let archives = sink.archives().unwrap();
+    assert_eq!(archives.len(), 2);
+    assert!(std::fs::metadata(&path).unwrap().len() <= 200);
+    let newest = std::fs::read_to_string(archives.last().unwrap()).unwrap();
+    let current = std::fs::read_to_string(&path).unwrap();
+    assert!(current.contains("msg=\"message 9\""), "{current}");
+    assert!(!newest.contains("message 9"));
+    drop(sink);
+
+    // A file last written yesterday is rotated on the first write today.
+    let daily = dir.path().join("daily.log");
+    std::fs::write(&daily, "old entry\n").unwrap();
+    let yesterday = std::time::SystemTime::now() - std::time::Duration::from_secs(25 * 3600);
+    std::fs::File::options()
+        .write(true)
+        .open(&daily)
+        .unwrap()
+        .set_modified(yesterday)
+        .unwrap();
+
+    let sink = RotatingFileSink::open(&daily)
+        .unwrap()
+        .period(Period::Daily);
+    sink.write(&record("new entry")).unwrap();
+    drop(sink);
+
+    let archives: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.starts_with("daily.") && name != "daily.log")
+        .collect();
+    assert_eq!(archives.len(), 1, "{archives:?}");
+    let current = std::fs::read_to_string(&daily).unwrap();
+    assert!(current.contains("[INFO] new entry") && !current.contains("old entry"));
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn test_log_crate_bridge() {
+    let _lock = global_logger();
+    let memory = MemorySink::new();
+    set_logger(Logger::new().level(LogLevel::Debug).sink(memory.clone()));
+    install_log_bridge().unwrap();
+    assert!(install_log_bridge().is_err());
+
+    log::trace!("filtered");
+    log::warn!(peer = "db", retries = 3; "retrying {}", "query");
+    log::error!(sysx_level = "fatal"; "disk gone");
+
+    let records = memory.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].level, LogLevel::Warning);
+    assert_eq!(records[0].message, "retrying query");
+    assert_eq!(records[0].module, module_path!());
+    assert_eq!(records[0].field("retries"), Some(&Value::Int(3)));
+    assert_eq!(records[1].level, LogLevel::Fatal);
+    assert!(records[1].fields.is_empty());
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn test_tracing_layer() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let _lock = global_logger();
+    let memory = MemorySink::new();
+    set_logger(Logger::new().level(LogLevel::Info).sink(memory.clone()));
+
+    let subscriber = tracing_subscriber::registry().with(SysxLayer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::debug!("filtered");
+        tracing::info!(
+            peer = "db",
+            retries = 3u64,
+            ok = true,
+            "connected to {}",
+            "db"
+        );
+        tracing::info!(sysx_level = "success", "deployed");
+    });
+
+    let records = memory.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].level, LogLevel::Info);
+    assert_eq!(records[0].message, "connected to db");
+    assert_eq!(records[0].field("peer"), Some(&Value::Str("db".into())));
+    assert_eq!(records[0].field("retries"), Some(&Value::UInt(3)));
+    assert_eq!(records[0].field("ok"), Some(&Value::Bool(true)));
+    assert_eq!(records[1].level, LogLevel::Success);
+
+    // A callsite filtered once is checked again after the logger changes.
+    let debug = |message: &str| tracing::debug!("{message}");
+    let subscriber = tracing_subscriber::registry().with(SysxLayer);
+    tracing::subscriber::with_default(subscriber, || {
+        debug("before");
+        set_logger(Logger::new().level(LogLevel::Debug).sink(memory.clone()));
+        debug("after");
+    });
+    let records = memory.records();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[2].message, "after");
+}
+
+#[test]
+fn test_spans_and_timers() {
+    use sysx::time::{Duration, SleepTime, humanize};
+
+    assert_eq!(humanize(Duration::from_millis(1500)), "1.5s");
+    assert_eq!(humanize(Duration::from_micros(250)), "250us");
+    assert_eq!(humanize(Duration::from_secs(5400)), "1.5h");
+    assert_eq!(humanize(Duration::ZERO), "0ns");
+    assert_eq!(humanize(Duration::from_millis(59_999)), "1m");
+    assert_eq!(humanize(Duration::from_millis(3_599_999)), "1h");
+    assert_eq!(humanize(Duration::from_nanos(999_996)), "1ms");
+    let shown = humanize(Duration::from_millis(1234));
+    assert_eq!(shown, "1.23s");
+    assert_eq!(
+        shown.parse::<SleepTime>().unwrap().to_duration(),
+        Duration::from_millis(1230)
+    );
+
+    let _lock = global_logger();
+    let memory = MemorySink::new();
+    set_logger(Logger::new().level(LogLevel::Debug).sink(memory.clone()));
+
+    {
+        let _outer = span!(INFO, "load {}", "config");
+        log!(DEBUG, "inside");
+        let _timer = timer!(DEBUG, "parse");
+        let _hidden = span!(TRACE, "filtered");
+        assert_eq!(span_depth(), 2);
+    }
+    assert_eq!(span_depth(), 0);
+
+    let records = memory.records();
+    let summary: Vec<_> = records
+        .iter()
+        .map(|r| (r.depth, r.message.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (0, "load config started"),
+            (1, "inside"),
+            (1, "parse finished"),
+            (0, "load config finished"),
+        ]
+    );
+    assert!(matches!(records[3].field("elapsed"), Some(Value::Str(s)) if s.ends_with('s')));
+    assert!(format_pretty(&records[1], false).contains("[DEBUG]   inside"));
+}
+
+#[test]
+fn test_async_sink_overflow() {
+    struct SlowSink(MemorySink);
+
+    impl Sink for SlowSink {
+        fn write(&self, record: &Record) -> sysx::Result<()> {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            self.0.write(record)
+        }
+    }
+
+    let record = |level, i: usize| Record::new(level, i.to_string(), None, "app", "app.rs", 1);
+    let run = |overflow| {
+        let memory = MemorySink::new();
+        let sink = AsyncSink::new(SlowSink(memory.clone()), 2, overflow).unwrap();
+        for i in 0..10 {
+            sink.write(&record(LogLevel::Info, i)).unwrap();
+        }
+        sink.write(&record(LogLevel::Fatal, 10)).unwrap();
+        // Fatal records are written before `write` returns.
+        assert_eq!(memory.messages().last().unwrap(), "10");
+        assert_eq!(sink.pending(), 0);
+
+        drop(sink.shutdown_guard());
+        // After shutdown, records are written synchronously.
+        sink.write(&record(LogLevel::Info, 11)).unwrap();
+        memory.records()
+    };
+
+    let blocked = run(Overflow::Block);
+    let messages: Vec<_> = blocked.iter().map(|r| r.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11"]
+    );
+
+    for overflow in [Overflow::DropNewest, Overflow::DropOldest] {
+        let records = run(overflow);
+        let dropped: u64 = records
+            .iter()
+            .filter_map(|r| match r.field("dropped") {
+                Some(Value::UInt(n)) => Some(*n),
+                _ => None,
+            })
+            .sum();
+        let kept = records
+            .iter()
+            .filter(|r| r.field("dropped").is_none())
+            .count();
+        assert!(dropped > 0, "{overflow:?}");
+        assert_eq!(kept as u64 + dropped, 12, "{overflow:?}");
+        assert!(records.iter().any(|r| r.level == LogLevel::Fatal));
+        if overflow == Overflow::DropOldest {
+            assert!(records.iter().any(|r| r.message == "9"));
+        }
+    }
+}
+
+#[test]
+fn test_log_capture() {
+    // Captured records still reach the global logger, so keep them out of other tests' sinks.
+    let _lock = global_logger();
+    set_logger(Logger::new());
+    let logs = capture_logs();
+    log!(WARNING, "request timeout after {}s", 5; attempt = 2);
+    log!(TRACE, "very detailed");
+    {
+        let inner = capture_logs();
+        log!(INFO, "inner only");
+        assert_eq!(inner.messages(), ["inner only"]);
+    }
+    log!(ERROR, "failed"; "while connecting");
+
+    assert_logged!(WARNING, contains "timeout");
+    assert_logged!(WARNING, field "attempt" == 2);
+    assert_logged!(TRACE, "very detailed");
+    assert_not_logged!(INFO, "inner only");
+    assert_not_logged!(ERROR, contains "timeout");
+    assert_eq!(
+        logs.records()[2].context.as_deref(),
+        Some("while connecting")
+    );
+    assert!(logs.any(LogLevel::Error, |r| r.message == "failed"));
+
+    // Records from other threads go to their own capture.
+    std::thread::spawn(|| log!(WARNING, "other thread"))
+        .join()
+        .unwrap();
+    assert_not_logged!(WARNING, "other thread");
+
+    let failure = std::panic::catch_unwind(|| assert_logged!(BUG, contains "missing"));
+    let message = *failure.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("request timeout after 5s"), "{message}");
+
+    logs.clear();
+    assert!(logs.records().is_empty());
+    drop(logs);
+    assert!(!is_capturing());
+}
+
+#[test]
+fn test_log_themes() {
+    let record =
+        Record::new(LogLevel::Error, "disk full", None, "app", "app.rs", 1).with_fields(vec![
+            ("free".into(), Value::UInt(0)),
+            ("mount".into(), Value::Str("/".into())),
+        ]);
+
+    let classic = format_pretty_with(&record, &LogTheme::default(), true);
+    assert!(
+        classic.contains("\u{1b}[1;31m[ERROR] disk full\u{1b}[0m"),
+        "{classic:?}"
+    );
+
+    let mono = LogTheme::monochrome();
+    let plain = format_pretty_with(&record, &mono, true);
+    assert!(
+        plain.contains("\u{1b}[1m[ERROR] disk full\u{1b}[0m"),
+        "{plain:?}"
+    );
+    assert_eq!(mono.level_style(LogLevel::Info).fg, None);
+    assert!(!mono.level_style(LogLevel::Info).bold);
+
+    let contrast = LogTheme::high_contrast();
+    assert_eq!(contrast.level_style(LogLevel::Error).bg, Some(Color::Red));
+    assert!(format_pretty_with(&record, &contrast, true).contains("\u{1b}[1;97;41m"));
+
+    let custom = LogTheme::default()
+        .prefix(LogLevel::Error, "🔥")
+        .timestamp_format("%H:%M")
+        .field_separator(", ");
+    assert_eq!(
+        format_pretty_with(&record, &custom, false),
+        format!(
+            "{} 🔥 disk full free=0, mount=/",
+            record.timestamp.format("%H:%M")
+        )
+    );
+
+    // SAFETY (all guards below): every test in this file that touches the environment
+    // holds `env_lock`.
+    {
+        let _env = unsafe { EnvGuard::new() }
+            .set("NO_COLOR", "1")
+            .set("CLICOLOR_FORCE", "1");
+        assert!(!should_color(true));
+    }
+    {
+        let _env = unsafe { EnvGuard::new() }
+            .remove("NO_COLOR")
+            .set("CLICOLOR_FORCE", "1");
+        assert!(should_color(false));
+    }
+    {
+        let _env = unsafe { EnvGuard::new() }
+            .set("NO_COLOR", "")
+            .set("CLICOLOR_FORCE", "0");
+        assert!(should_color(true));
+        assert!(!should_color(false));
+    }
+}
@@ -0,0 +1,43 @@
+use std::io::Cursor;
+
+use sysx::io::prompt::*;
+
+fn prompter(answers: &str) -> Prompter<Cursor<Vec<u8>>, Vec<u8>> {
+    Prompter::new(Cursor::new(answers.as_bytes().to_vec()), Vec::new())
+}
+
+#[test]
+fn test_confirm_and_select() {
+    let mut p = prompter("maybe\nY\n\n\n");
+    assert!(p.confirm("Continue?", None).unwrap());
+    assert!(!p.confirm("Delete?", Some(false)).unwrap());
+    assert!(p.confirm("Keep?", Some(true)).unwrap());
+    assert!(p.confirm("Again?", None).is_err()); // end of input
+
+    let mut p = prompter("0\nthree\n2\n3, 1 3\n\n");
+    assert_eq!(p.select("Pick one", &["a", "b", "c"]).unwrap(), 1);
+    assert_eq!(
+        p.multi_select("Pick many", &["a", "b", "c"]).unwrap(),
+        [0, 2]
+    );
+    assert!(p.multi_select("Pick none", &["a"]).unwrap().is_empty());
+}
+
+#[test]
+fn test_typed_and_validated_prompts() {
+    let mut p = prompter("abc\n42\nshort\nlong enough\nsecret\n");
+    assert_eq!(p.prompt::<u32>("Age:").unwrap(), 42);
+
+    let name = p
+        .validated("Name:", |s| {
+            if s.len() >= 8 {
+                Ok(())
+            } else {
+                Err("Too short".into())
+            }
+        })
+        .unwrap();
+    assert_eq!(name, "long enough");
+
+    assert_eq!(p.password("Password:").unwrap(), "secret");
+}
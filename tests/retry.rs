@@ -0,0 +1,95 @@
+use std::time::{Duration, Instant};
+
+use sysx::{SysxError, io::cmd::Cmd, time::*};
+
+#[test]
+fn test_backoff_delays() {
+    let linear = Backoff::linear(10u64, "5ms").unwrap();
+    assert_eq!(linear.delay(1).unwrap(), Duration::from_millis(10));
+    assert_eq!(linear.delay(3).unwrap(), Duration::from_millis(20));
+
+    let exp = Backoff::Exponential {
+        initial: Duration::from_millis(100),
+        factor:  2.0,
+        max:     Duration::from_millis(500),
+        jitter:  false,
+    };
+    assert_eq!(exp.delay(3).unwrap(), Duration::from_millis(400));
+    assert_eq!(exp.delay(10).unwrap(), Duration::from_millis(500));
+
+    // Huge caps must not overflow the f64 -> Duration conversion.
+    let unbounded = Backoff::Exponential {
+        initial: Duration::from_secs(1),
+        factor:  2.0,
+        max:     Duration::MAX,
+        jitter:  false,
+    };
+    assert_eq!(unbounded.delay(2000).unwrap(), Duration::MAX);
+    // ... nor the deadline check that adds the delay to the elapsed time.
+    let huge = Backoff::Exponential {
+        initial: Duration::MAX,
+        factor:  2.0,
+        max:     Duration::MAX,
+        jitter:  true,
+    };
+    for backoff in [huge, Backoff::Fixed(Duration::MAX)] {
+        let mut calls = 0;
+        let result: sysx::Result<()> =
+            RetryPolicy::new(backoff).deadline("1h").unwrap().run(|| {
+                calls += 1;
+                Err(SysxError::InvalidSyntax("always".into()))
+            });
+        assert!(matches!(result, Err(SysxError::InvalidSyntax(_))));
+        assert_eq!(calls, 1);
+    }
+
+    let jittered = Backoff::exponential("100ms", "1s").unwrap();
+    let delay = jittered.delay(2).unwrap();
+    assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+
+    assert!(Backoff::fixed("later").is_err());
+}
+
+#[test]
+fn test_retry_policy() {
+    let policy = RetryPolicy::new(Backoff::fixed(1u64).unwrap()).max_attempts(5);
+
+    let mut calls = 0;
+    let value = policy
+        .run(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(SysxError::InvalidSyntax("flaky".into()))
+            } else {
+                Ok(calls)
+            }
+        })
+        .unwrap();
+    assert_eq!(value, 3);
+
+    // non-retryable errors are returned immediately
+    let mut calls = 0;
+    let result: sysx::Result<()> = policy
+        .clone()
+        .retry_if(|e| matches!(e, SysxError::CommandTimeout { .. }))
+        .run(|| {
+            calls += 1;
+            Err(SysxError::InvalidSyntax("fatal".into()))
+        });
+    assert!(result.is_err());
+    assert_eq!(calls, 1);
+
+    // deadline stops retrying early
+    let started = Instant::now();
+    let result: sysx::Result<()> = RetryPolicy::new(Backoff::fixed("50ms").unwrap())
+        .max_attempts(100)
+        .deadline("120ms")
+        .unwrap()
+        .run(|| Err(SysxError::InvalidSyntax("always".into())));
+    assert!(result.is_err());
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    // commands are retried on non-zero exit
+    let err = policy.run_cmd(&Cmd::new("false")).unwrap_err();
+    assert!(matches!(err, SysxError::CommandFailed { .. }));
+}